@@ -0,0 +1,132 @@
+//! Cycles per byte and instructions per cycle of every algorithm at every chunk size, counted
+//! with the CPU performance counters rather than timed by criterion.
+//!
+//! ```text
+//! cargo bench --bench cycles -- xxh3 blake3
+//! ```
+//!
+//! Only the algorithms containing one of the arguments are measured, if any are given. Criterion's
+//! options are accepted and ignored, so `cargo bench` can pass them to every benchmark. The
+//! reports are printed, and written to `cycles.json` next to the run manifest.
+
+use hash_bench::{
+    cpu_info::CpuInfo,
+    cycles::{cycles_per_byte, CounterSource, CycleCounter},
+    manifest::{criterion_dir, RunManifest},
+    matrix::BenchMatrix,
+    registry::HasherRegistry,
+};
+use std::{env, fs, process};
+
+/// Criterion's options which take a value, as a separate argument or after `=`.
+const VALUE_OPTIONS: &[&str] = &[
+    "-c",
+    "--color",
+    "--colour",
+    "-s",
+    "--save-baseline",
+    "-b",
+    "--baseline",
+    "--load-baseline",
+    "--profile-time",
+    "--sample-size",
+    "--warm-up-time",
+    "--measurement-time",
+    "--nresamples",
+    "--noise-threshold",
+    "--confidence-level",
+    "--significance-level",
+    "--plotting-backend",
+    "--output-format",
+];
+
+/// The positional arguments, skipping options and their values the way criterion parses them.
+fn filters(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut filters = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            filters.extend(args);
+            break;
+        }
+        if !arg.starts_with('-') {
+            filters.push(arg);
+        } else if VALUE_OPTIONS.contains(&arg.as_str()) {
+            args.next();
+        }
+    }
+    filters
+}
+
+fn main() {
+    let registry = HasherRegistry::builtin();
+    let mut matrix = match BenchMatrix::from_env(&registry) {
+        Ok(matrix) => matrix,
+        Err(e) => {
+            eprintln!("invalid benchmark matrix: {e}");
+            process::exit(2);
+        }
+    };
+    let filters = filters(env::args().skip(1));
+    if !filters.is_empty() {
+        matrix
+            .algorithms
+            .retain(|a| filters.iter().any(|f| a.contains(f.as_str())));
+        // Like criterion, a filter matching nothing isn't an error.
+        if matrix.algorithms.is_empty() {
+            println!("no algorithm matches {filters:?}, skipping");
+            return;
+        }
+    }
+    if let Err(e) = matrix.validate(&registry) {
+        eprintln!("invalid benchmark matrix: {e}");
+        process::exit(2);
+    }
+    // Only the chunked input is measured.
+    matrix.input_sizes.clear();
+    matrix.thread_counts = vec![1];
+
+    println!("{}", CpuInfo::detect());
+    let Some(counter) = CycleCounter::new() else {
+        println!("no cycle counter available on this platform, skipping");
+        return;
+    };
+    println!("counting {}", counter.source());
+    if counter.source() == CounterSource::Rdtsc {
+        if let Err(e) = CycleCounter::perf() {
+            println!("perf_event_open unavailable ({e}), so instructions aren't counted");
+        }
+    }
+
+    let dir = criterion_dir();
+    match RunManifest::new("cycles", &matrix).write(&dir) {
+        Ok(path) => println!("run manifest written to {}", path.display()),
+        Err(e) => panic!("can't write run manifest: {e}"),
+    }
+
+    let mut reports = Vec::new();
+    for pattern in &matrix.patterns {
+        let data = match pattern.generate(matrix.chunked_input_size, matrix.seed) {
+            Ok(data) => data,
+            Err(e) => panic!("can't generate benchmark input: {e}"),
+        };
+        for algorithm in &matrix.algorithms {
+            for &chunk_size in &matrix.chunk_sizes {
+                match cycles_per_byte(&counter, &registry, algorithm, &data, chunk_size) {
+                    Ok(report) => {
+                        println!("{pattern}: {report}");
+                        reports.push((pattern.to_string(), report));
+                    }
+                    Err(e) => panic!("can't measure {algorithm}: {e}"),
+                }
+            }
+        }
+    }
+
+    let path = dir.join("cycles.json");
+    let json = serde_json::to_string_pretty(&reports).expect("reports are serializable");
+    match fs::write(&path, json) {
+        Ok(()) => println!("reports written to {}", path.display()),
+        Err(e) => panic!("can't write reports: {e}"),
+    }
+}
@@ -0,0 +1,73 @@
+//! Latency of hashing a single short message: every iteration constructs a hasher, feeds it the
+//! whole message at once and finalizes it, so setup and finalization are not amortized.
+//!
+//! Messages are every size from 0 to 256 bytes, and powers of two up to 16 KiB beyond that. With
+//! that many points, a run is best limited to the algorithms of interest:
+//!
+//! ```text
+//! cargo bench --bench latency -- "xxh3-64 latency"
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hash_bench::{
+    manifest::{criterion_dir, RunManifest},
+    matrix::{BenchMatrix, DataPattern},
+    registry::HasherRegistry,
+};
+use std::time::Duration;
+
+fn message_sizes() -> impl Iterator<Item = usize> {
+    (0..=256).chain((9..=14).map(|i| 1 << i))
+}
+
+fn latency(c: &mut Criterion) {
+    let registry = HasherRegistry::builtin();
+    let matrix = match BenchMatrix::from_env(&registry) {
+        // Messages are hashed in a single piece, and only random messages are measured.
+        Ok(matrix) => BenchMatrix {
+            chunk_sizes: Vec::new(),
+            chunked_input_size: 0,
+            input_sizes: message_sizes().collect(),
+            patterns: vec![DataPattern::Random],
+            ..matrix
+        },
+        Err(e) => panic!("invalid benchmark matrix: {e}"),
+    };
+    match RunManifest::new("latency", &matrix).write(&criterion_dir()) {
+        Ok(path) => println!("run manifest written to {}", path.display()),
+        Err(e) => panic!("can't write run manifest: {e}"),
+    }
+    let message = DataPattern::Random
+        .generate(matrix.max_input_size(), matrix.seed)
+        .unwrap();
+
+    for algorithm in &matrix.algorithms {
+        let digest = registry.digester(algorithm).unwrap();
+        let mut out = Vec::with_capacity(registry.output_len(algorithm).unwrap());
+        let mut group = c.benchmark_group(format!("{algorithm} latency"));
+        for len in message_sizes() {
+            group.bench_with_input(
+                BenchmarkId::from_parameter(len),
+                &message[..len],
+                |b, message| {
+                    b.iter(|| {
+                        digest(black_box(message), &mut out);
+                        black_box(&out);
+                    })
+                },
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    // Short messages take nanoseconds, so far fewer seconds per point suffice than for the
+    // throughput benchmarks.
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(200))
+        .measurement_time(Duration::from_millis(500));
+    targets = latency
+}
+criterion_main!(benches);
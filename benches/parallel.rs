@@ -0,0 +1,127 @@
+//! Scaling of the multi-threaded BLAKE3 modes with the number of threads, on inputs large enough
+//! to be split. The single-threaded BLAKE3 and the SIMD parallel BLAKE2bp and BLAKE2sp are
+//! measured alongside, as a baseline.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hash_bench::{
+    hashers::blake3_mmap_rayon,
+    manifest::{criterion_dir, RunManifest},
+    matrix::BenchMatrix,
+    registry::HasherRegistry,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// BLAKE3 only splits updates of at least this size over multiple threads.
+const MIN_PARALLEL_INPUT: usize = 128 << 10;
+
+/// Inputs from this size on are measured with the minimal number of samples.
+const LARGE_INPUT: usize = 64 << 20;
+
+/// Single-threaded algorithms to compare against.
+const BASELINES: &[&str] = &["blake3-256", "blake2b-512", "blake2bp-512", "blake2sp-256"];
+
+/// An input file in the system temporary directory, removed when dropped, which includes
+/// unwinding from a failed benchmark.
+struct InputFile(PathBuf);
+
+impl InputFile {
+    fn create(name: &str, bytes: &[u8]) -> Self {
+        let path = env::temp_dir().join(format!("hash_bench-{}-{name}", process::id()));
+        if let Err(e) = fs::write(&path, bytes) {
+            panic!("can't write benchmark input {}: {e}", path.display());
+        }
+        Self(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for InputFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn parallel(c: &mut Criterion) {
+    let registry = HasherRegistry::parallel();
+    let mut matrix = match BenchMatrix::from_env(&registry) {
+        Ok(matrix) => matrix,
+        Err(e) => panic!("invalid benchmark matrix: {e}"),
+    };
+    matrix.algorithms = ["blake3-256-rayon"]
+        .into_iter()
+        .chain(BASELINES.iter().copied())
+        .map(String::from)
+        .collect();
+    matrix.input_sizes.retain(|&len| len >= MIN_PARALLEL_INPUT);
+    if let Err(e) = matrix.validate(&registry) {
+        panic!("invalid benchmark matrix: {e}");
+    }
+    // Inputs are hashed in a single update.
+    matrix.chunk_sizes.clear();
+    let mut manifest = RunManifest::new("parallel", &matrix);
+    manifest.functions.push("blake3-256-mmap-rayon".into());
+    match manifest.write(&criterion_dir()) {
+        Ok(path) => println!("run manifest written to {}", path.display()),
+        Err(e) => panic!("can't write run manifest: {e}"),
+    }
+
+    for (i, pattern) in matrix.patterns.iter().enumerate() {
+        let buffer = match pattern.generate(matrix.max_input_size(), matrix.seed) {
+            Ok(buffer) => buffer,
+            Err(e) => panic!("can't generate benchmark input: {e}"),
+        };
+        for &len in &matrix.input_sizes {
+            let bytes = &buffer[..len];
+            // The memory mapped mode hashes a file, which is likely in the page cache after the
+            // first iteration.
+            // Patterns may contain paths, so they're numbered in the file name.
+            let input = InputFile::create(&format!("parallel-{i}-{len}"), bytes);
+
+            let mut group = c.benchmark_group(format!("parallel {pattern}/{len}"));
+            group.throughput(Throughput::Bytes(len as u64));
+            group.sample_size(if len >= LARGE_INPUT { 10 } else { 100 });
+            for &threads in &matrix.thread_counts {
+                let pool = match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                    Ok(pool) => pool,
+                    Err(e) => panic!("can't start {threads} threads: {e}"),
+                };
+                group.bench_with_input(
+                    BenchmarkId::new("blake3-256-rayon", threads),
+                    bytes,
+                    |b, bytes| {
+                        b.iter(|| {
+                            let mut hasher = registry.get("blake3-256-rayon").unwrap();
+                            pool.install(|| hasher.update(black_box(bytes)));
+                            black_box(hasher.finalize());
+                        })
+                    },
+                );
+                group.bench_with_input(
+                    BenchmarkId::new("blake3-256-mmap-rayon", threads),
+                    input.path(),
+                    |b, path| b.iter(|| black_box(pool.install(|| blake3_mmap_rayon(path)))),
+                );
+            }
+            for algorithm in BASELINES {
+                group.bench_with_input(BenchmarkId::new(*algorithm, 1), bytes, |b, bytes| {
+                    b.iter(|| {
+                        let mut hasher = registry.get(algorithm).unwrap();
+                        hasher.update(black_box(bytes));
+                        black_box(hasher.finalize());
+                    })
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, parallel);
+criterion_main!(benches);
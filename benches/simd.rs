@@ -0,0 +1,94 @@
+//! Every SIMD backend of BLAKE3, BLAKE2b and CRC32 supported by this CPU, to show the gain of each
+//! instruction set over the portable implementation. The BLAKE3 group only measures the
+//! compression of whole chunks, not the full hash.
+//!
+//! ```text
+//! cargo bench --features simd-backends --bench simd
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hash_bench::{
+    manifest::{criterion_dir, RunManifest},
+    matrix::BenchMatrix,
+    registry::HasherRegistry,
+    simd::{backends, BLAKE3_CHUNK_COMPRESSION},
+};
+use std::collections::HashSet;
+
+/// Input sizes, from a few BLAKE3 chunks to well past the L2 cache, all whole numbers of chunks.
+const INPUT_SIZES: &[usize] = &[4 << 10, 64 << 10, 1 << 20];
+
+fn simd(c: &mut Criterion) {
+    let registry = HasherRegistry::builtin();
+    let mut matrix = match BenchMatrix::from_env(&registry) {
+        Ok(matrix) => matrix,
+        Err(e) => panic!("invalid benchmark matrix: {e}"),
+    };
+    let backends = backends();
+    for backend in &backends {
+        println!("{backend}");
+    }
+    // The backends available on this CPU are recorded, as they differ between machines.
+    let mut manifest_backends: Vec<_> = backends
+        .iter()
+        .map(|b| format!("{}/{}", b.algorithm, b.name))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut algorithms: Vec<_> = backends.iter().map(|b| b.algorithm).collect();
+    algorithms.retain(|a| seen.insert(*a));
+    matrix.algorithms = algorithms
+        .iter()
+        .filter(|&&a| a != BLAKE3_CHUNK_COMPRESSION)
+        .map(|a| a.to_string())
+        .collect();
+    matrix.input_sizes = INPUT_SIZES.to_vec();
+    matrix.thread_counts = vec![1];
+    if let Err(e) = matrix.validate(&registry) {
+        panic!("invalid benchmark matrix: {e}");
+    }
+    // Inputs are hashed in a single call.
+    matrix.chunk_sizes.clear();
+    let mut manifest = RunManifest::new("simd", &matrix);
+    manifest.functions.append(&mut manifest_backends);
+    match manifest.write(&criterion_dir()) {
+        Ok(path) => println!("run manifest written to {}", path.display()),
+        Err(e) => panic!("can't write run manifest: {e}"),
+    }
+
+    let buffers: Vec<_> = matrix
+        .patterns
+        .iter()
+        .map(
+            |pattern| match pattern.generate(matrix.max_input_size(), matrix.seed) {
+                Ok(buffer) => (pattern, buffer),
+                Err(e) => panic!("can't generate benchmark input: {e}"),
+            },
+        )
+        .collect();
+
+    for algorithm in algorithms {
+        let mut group = c.benchmark_group(format!("{algorithm} backends"));
+        for (pattern, buffer) in &buffers {
+            for &len in &matrix.input_sizes {
+                group.throughput(Throughput::Bytes(len as u64));
+                for backend in backends.iter().filter(|b| b.algorithm == algorithm) {
+                    group.bench_with_input(
+                        BenchmarkId::new(format!("{}/{pattern}", backend.name), len),
+                        &buffer[..len],
+                        |b, bytes| {
+                            let mut out = Vec::new();
+                            b.iter(|| {
+                                backend.hash(black_box(bytes), &mut out);
+                                black_box(&out);
+                            })
+                        },
+                    );
+                }
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, simd);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+//! Records how the crate was built, for the run manifests of the benchmarks.
+
+use std::{env, path::Path, process::Command};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let manifest_dir = Path::new(&manifest_dir);
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|v| v.trim().to_owned())
+        .unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=HASH_BENCH_RUSTC_VERSION={rustc_version}");
+
+    println!(
+        "cargo:rustc-env=HASH_BENCH_TARGET={}",
+        env::var("TARGET").unwrap()
+    );
+    println!(
+        "cargo:rustc-env=HASH_BENCH_PROFILE={}",
+        env::var("PROFILE").unwrap()
+    );
+    println!(
+        "cargo:rustc-env=HASH_BENCH_TARGET_FEATURES={}",
+        env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default()
+    );
+
+    println!(
+        "cargo:rustc-env=HASH_BENCH_DEPENDENCIES={}",
+        dependencies(manifest_dir).join(",")
+    );
+}
+
+/// `name=version` for every normal dependency. The versions are those of the lock file if there
+/// is one, which there isn't when this crate is built as a dependency, in which case the version
+/// requirements of the manifest are recorded instead.
+fn dependencies(manifest_dir: &Path) -> Vec<String> {
+    let metadata = |args: &[&str]| -> Option<serde_json::Value> {
+        let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+        let output = Command::new(cargo)
+            .args(["metadata", "--format-version", "1", "--offline"])
+            .args(args)
+            .arg("--manifest-path")
+            .arg(manifest_dir.join("Cargo.toml"))
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        serde_json::from_slice(&output.stdout).ok()
+    };
+    let package = env::var("CARGO_PKG_NAME").unwrap();
+
+    // `--locked` fails rather than writing a lock file into the source directory.
+    if let Some(metadata) = metadata(&["--locked"]) {
+        let packages = metadata["packages"].as_array().unwrap();
+        let id = |id: &serde_json::Value| packages.iter().find(|p| p["id"] == *id);
+        let root = packages.iter().find(|p| p["name"] == package).unwrap();
+        let node = metadata["resolve"]["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|n| n["id"] == root["id"])
+            .unwrap();
+        let mut versions: Vec<_> = node["deps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|d| {
+                d["dep_kinds"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|k| k["kind"].is_null())
+            })
+            .filter_map(|d| id(&d["pkg"]))
+            .map(|p| {
+                format!(
+                    "{}={}",
+                    p["name"].as_str().unwrap(),
+                    p["version"].as_str().unwrap()
+                )
+            })
+            .collect();
+        versions.sort();
+        return versions;
+    }
+
+    let Some(metadata) = metadata(&["--no-deps"]) else {
+        return Vec::new();
+    };
+    let root = metadata["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == package)
+        .unwrap();
+    let mut versions: Vec<_> = root["dependencies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["kind"].is_null())
+        .map(|d| {
+            format!(
+                "{}={}",
+                d["name"].as_str().unwrap(),
+                d["req"].as_str().unwrap()
+            )
+        })
+        .collect();
+    versions.sort();
+    versions
+}
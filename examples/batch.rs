@@ -0,0 +1,83 @@
+//! Hashes a batch of independent messages on a pool of workers, for every registered hasher or
+//! only the hashers named on the command line, and reports the aggregate throughput and the
+//! latency percentiles of single messages.
+//!
+//! ```text
+//! cargo run --release --example batch -- xxh3-64 sha256
+//! ```
+//!
+//! Every batch holds 64 MiB of messages, or `HASH_BENCH_BATCH_BYTES`. Messages are 64 bytes,
+//! 1 KiB, 16 KiB and 1 MiB long, or the comma separated sizes of `HASH_BENCH_MESSAGE_SIZES`.
+//! The worker counts, data patterns and seed are those of the benchmark matrix, so can be set
+//! with `HASH_BENCH_THREADS`, `HASH_BENCH_PATTERNS` and `HASH_BENCH_SEED`.
+
+use hash_bench::{batch::hash_batch, matrix::BenchMatrix, registry::HasherRegistry};
+use std::{env, process};
+
+fn env_sizes(var: &str, default: Vec<usize>) -> Vec<usize> {
+    match env::var(var) {
+        Ok(sizes) => sizes
+            .split(',')
+            .map(|s| {
+                s.trim()
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{var} must be numbers"))
+            })
+            .collect(),
+        Err(_) => default,
+    }
+}
+
+fn main() {
+    let registry = HasherRegistry::builtin();
+    let mut matrix = match BenchMatrix::from_env(&registry) {
+        Ok(matrix) => matrix,
+        Err(e) => {
+            eprintln!("invalid benchmark matrix: {e}");
+            process::exit(2);
+        }
+    };
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        matrix.algorithms = args;
+    }
+    if let Err(e) = matrix.validate(&registry) {
+        eprintln!("invalid benchmark matrix: {e}");
+        process::exit(2);
+    }
+    let batch_bytes = env_sizes("HASH_BENCH_BATCH_BYTES", vec![64 << 20])[0];
+    let message_sizes = env_sizes(
+        "HASH_BENCH_MESSAGE_SIZES",
+        vec![64, 1 << 10, 16 << 10, 1 << 20],
+    );
+
+    for pattern in &matrix.patterns {
+        let buffer = match pattern.generate(batch_bytes, matrix.seed) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                eprintln!("can't generate batch: {e}");
+                process::exit(2);
+            }
+        };
+        for &size in &message_sizes {
+            // Every message is a separate part of the buffer, so no two workers hash the same
+            // memory.
+            let messages: Vec<_> = buffer.chunks_exact(size.max(1)).collect();
+            println!("{pattern}: {} messages of {size} bytes", messages.len());
+            for algorithm in &matrix.algorithms {
+                for &workers in &matrix.thread_counts {
+                    // The first round warms up caches and the page tables of the buffer.
+                    let report = hash_batch(&registry, algorithm, &messages, workers)
+                        .and_then(|_| hash_batch(&registry, algorithm, &messages, workers));
+                    match report {
+                        Ok(report) => println!("  {report}"),
+                        Err(e) => {
+                            eprintln!("{algorithm}: {e}");
+                            process::exit(2);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Reports the collisions of digests truncated to a number of widths, for every registered
+//! hasher or only the hashers named on the command line.
+//!
+//! ```text
+//! cargo run --release --example collisions -- xxh3-64 crc32
+//! ```
+//!
+//! The number of keys can be changed with `HASH_BENCH_KEYS`, the widths in bits with a comma
+//! separated `HASH_BENCH_WIDTHS`, and `HASH_BENCH_SEQUENTIAL=1` hashes sequential integers instead
+//! of random keys. The random keys are generated from `HASH_BENCH_SEED`, if set.
+
+use hash_bench::{
+    collisions::{collisions, CollisionConfig, KeySet},
+    matrix::seed_from_env,
+    registry::HasherRegistry,
+};
+use std::{env, process};
+
+/// Print `message` and exit with the status of invalid input.
+fn invalid(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(2);
+}
+
+fn main() {
+    let registry = HasherRegistry::builtin();
+    let mut algorithms: Vec<String> = env::args().skip(1).collect();
+    if algorithms.is_empty() {
+        algorithms = registry.names().map(String::from).collect();
+    }
+
+    let mut config = CollisionConfig::default();
+    match seed_from_env() {
+        Ok(seed) => config.seed = seed,
+        Err(e) => invalid(&e.to_string()),
+    }
+    if let Ok(keys) = env::var("HASH_BENCH_KEYS") {
+        config.keys = keys
+            .parse()
+            .unwrap_or_else(|_| invalid("HASH_BENCH_KEYS must be a number"));
+    }
+    if let Ok(widths) = env::var("HASH_BENCH_WIDTHS") {
+        config.widths = widths
+            .split(',')
+            .map(|w| {
+                w.trim()
+                    .parse()
+                    .unwrap_or_else(|_| invalid("HASH_BENCH_WIDTHS must be numbers"))
+            })
+            .collect();
+    }
+    if env::var("HASH_BENCH_SEQUENTIAL").is_ok_and(|v| v == "1") {
+        config.key_set = KeySet::Sequential;
+    }
+
+    println!("{} {} keys", config.keys, config.key_set);
+    for algorithm in &algorithms {
+        // Digests narrower than the widest width are only truncated to the widths they support.
+        let output_bits = match registry.output_len(algorithm) {
+            Some(len) => 8 * len as u32,
+            None => invalid(&format!("unknown algorithm {algorithm}")),
+        };
+        let config = CollisionConfig {
+            widths: config
+                .widths
+                .iter()
+                .copied()
+                .filter(|&w| w <= output_bits)
+                .collect(),
+            ..config.clone()
+        };
+        match collisions(&registry, algorithm, &config) {
+            Ok(reports) => reports.iter().for_each(|r| println!("{r}")),
+            Err(e) => invalid(&format!("{algorithm}: {e}")),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Prints a quality scorecard for every registered hasher, or only the hashers named on the
+//! command line.
+//!
+//! ```text
+//! cargo run --release --example quality -- crc32 xxh3-64
+//! ```
+//!
+//! The random keys are generated from `HASH_BENCH_SEED`, if set.
+
+use hash_bench::{
+    matrix::seed_from_env,
+    quality::{scorecard, QualityConfig},
+    registry::HasherRegistry,
+};
+use std::{env, process};
+
+fn main() {
+    let registry = HasherRegistry::builtin();
+    let mut algorithms: Vec<String> = env::args().skip(1).collect();
+    if algorithms.is_empty() {
+        algorithms = registry.names().map(String::from).collect();
+    }
+
+    let config = match seed_from_env() {
+        Ok(seed) => QualityConfig {
+            seed,
+            ..QualityConfig::default()
+        },
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(2);
+        }
+    };
+    let mut failed = Vec::new();
+    for algorithm in &algorithms {
+        match scorecard(&registry, algorithm, &config) {
+            Ok(card) => {
+                println!("{card}");
+                if !card.passed() {
+                    failed.push(card.algorithm);
+                }
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(2);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        println!("failed at least one test: {}", failed.join(", "));
+    }
+}
@@ -0,0 +1,44 @@
+//! Hashes arbitrary data with every registered hasher, in chunks of an arbitrary size, and checks
+//! the digest does not depend on the chunk size and a zero chunk size is reported as an error.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use hash_bench::{error::HashBenchError, registry::HasherRegistry};
+use libfuzzer_sys::fuzz_target;
+use std::sync::LazyLock;
+
+/// Building the registry finalizes every hasher once, so it's only done for the first input.
+static REGISTRY: LazyLock<HasherRegistry> = LazyLock::new(HasherRegistry::builtin);
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    chunk_size: u16,
+    data: Vec<u8>,
+}
+
+fuzz_target!(|input: Input| {
+    let chunk_size = input.chunk_size as usize;
+
+    let registry = &*REGISTRY;
+    for name in registry.names() {
+        let digest = match registry
+            .get(name)
+            .unwrap()
+            .try_hash(&input.data, chunk_size)
+        {
+            Ok(digest) => digest,
+            Err(e) => {
+                assert_eq!(chunk_size, 0, "{name}: {e}");
+                assert_eq!(e, HashBenchError::InvalidChunkSize);
+                continue;
+            }
+        };
+        let expected = registry
+            .get(name)
+            .unwrap()
+            .hash(&input.data, input.data.len().max(1));
+        assert_eq!(digest, expected, "{name}");
+        assert_eq!(Some(digest.len()), registry.output_len(name), "{name}");
+    }
+});
@@ -0,0 +1,42 @@
+//! Drives the streaming interface of every registered hasher with an arbitrary sequence of updates
+//! and resets, and checks the final digest equals the one shot digest of the data fed since the
+//! last reset.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use hash_bench::registry::HasherRegistry;
+use libfuzzer_sys::fuzz_target;
+use std::sync::LazyLock;
+
+/// Building the registry finalizes every hasher once, so it's only done for the first input.
+static REGISTRY: LazyLock<HasherRegistry> = LazyLock::new(HasherRegistry::builtin);
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Update(Vec<u8>),
+    Reset,
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let registry = &*REGISTRY;
+    for name in registry.names() {
+        let mut hasher = registry.get(name).unwrap();
+        let mut fed = Vec::new();
+        for op in &ops {
+            match op {
+                Op::Update(data) => {
+                    hasher.update(data);
+                    fed.extend_from_slice(data);
+                }
+                Op::Reset => {
+                    hasher.reset();
+                    fed.clear();
+                }
+            }
+        }
+
+        let expected = registry.get(name).unwrap().hash(&fed, fed.len().max(1));
+        assert_eq!(hasher.finalize(), expected, "{name}");
+    }
+});
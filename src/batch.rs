@@ -0,0 +1,162 @@
+//! Aggregate throughput of hashing many independent messages concurrently, as servers hashing
+//! separate objects do, rather than a single large buffer.
+
+use crate::{error::HashBenchError, registry::HasherRegistry};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Barrier,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The outcome of hashing a batch of messages with a pool of workers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchReport {
+    /// Name of the algorithm in the [`HasherRegistry`].
+    pub algorithm: String,
+    /// Number of worker threads.
+    pub workers: usize,
+    /// Number of messages in the batch.
+    pub messages: usize,
+    /// Total size of all messages in bytes.
+    pub bytes: usize,
+    /// Wall clock time from the workers starting on the first message until the last message was
+    /// hashed, which excludes spawning the worker threads.
+    pub elapsed: Duration,
+    /// Time taken by every single message, sorted from fastest to slowest.
+    pub latencies: Vec<Duration>,
+}
+
+impl BatchReport {
+    /// Aggregate throughput over all workers, in gigabytes (10^9 bytes) per second.
+    pub fn gigabytes_per_second(&self) -> f64 {
+        self.bytes as f64 / self.elapsed.as_secs_f64() / 1e9
+    }
+
+    /// The latency below which `percentile` percent of the messages were hashed, using the
+    /// nearest rank.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (percentile / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} with {} workers: {} messages, {} bytes in {:?}, {:.3} GB/s, latency p50 {:?} p90 {:?} p99 {:?} p99.9 {:?} max {:?}",
+            self.algorithm,
+            self.workers,
+            self.messages,
+            self.bytes,
+            self.elapsed,
+            self.gigabytes_per_second(),
+            self.percentile(50.0),
+            self.percentile(90.0),
+            self.percentile(99.0),
+            self.percentile(99.9),
+            self.percentile(100.0),
+        )
+    }
+}
+
+/// Hash every message in `messages` once with the algorithm registered as `name`, on `workers`
+/// threads taking the next unhashed message from a shared queue.
+pub fn hash_batch(
+    registry: &HasherRegistry,
+    name: &str,
+    messages: &[&[u8]],
+    workers: usize,
+) -> Result<BatchReport, HashBenchError> {
+    let digest = registry
+        .digester(name)
+        .ok_or_else(|| HashBenchError::UnknownAlgorithm(name.into()))?;
+    if workers == 0 {
+        return Err(HashBenchError::InvalidThreadCount);
+    }
+
+    let next = AtomicUsize::new(0);
+    // The workers start together once all of them are running.
+    let barrier = Barrier::new(workers);
+    let runs: Vec<(Instant, Instant, Vec<Duration>)> = thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut out = Vec::new();
+                    let mut latencies = Vec::new();
+                    barrier.wait();
+                    let start = Instant::now();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(message) = messages.get(i) else {
+                            break;
+                        };
+                        let started = Instant::now();
+                        digest(message, &mut out);
+                        latencies.push(started.elapsed());
+                    }
+                    (start, Instant::now(), latencies)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    let start = runs.iter().map(|&(start, _, _)| start).min().unwrap();
+    let end = runs.iter().map(|&(_, end, _)| end).max().unwrap();
+    let elapsed = end - start;
+    let mut latencies: Vec<Duration> = runs
+        .into_iter()
+        .flat_map(|(_, _, latencies)| latencies)
+        .collect();
+    latencies.sort_unstable();
+
+    Ok(BatchReport {
+        algorithm: name.into(),
+        workers,
+        messages: messages.len(),
+        bytes: messages.iter().map(|m| m.len()).sum(),
+        elapsed,
+        latencies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hash_batch;
+    use crate::{error::HashBenchError, registry::HasherRegistry};
+    use std::time::Duration;
+
+    #[test]
+    fn batch_covers_every_message() {
+        let registry = HasherRegistry::builtin();
+        let data = vec![7; 100 * 64];
+        let messages: Vec<_> = data.chunks(64).collect();
+
+        let report = hash_batch(&registry, "xxh3-64", &messages, 3).unwrap();
+        assert_eq!(report.messages, 100);
+        assert_eq!(report.bytes, 6400);
+        assert_eq!(report.latencies.len(), 100);
+        assert!(report.percentile(50.0) <= report.percentile(99.0));
+        assert_eq!(report.percentile(100.0), report.latencies[99]);
+        assert!(report.elapsed > Duration::ZERO);
+
+        assert_eq!(
+            hash_batch(&registry, "xxh3-64", &messages, 0),
+            Err(HashBenchError::InvalidThreadCount)
+        );
+        assert_eq!(
+            hash_batch(&registry, "sha1", &messages, 1),
+            Err(HashBenchError::UnknownAlgorithm("sha1".into()))
+        );
+    }
+}
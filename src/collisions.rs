@@ -0,0 +1,223 @@
+//! Collision experiments for truncated digests, to compare the collisions observed when keys are
+//! hashed to a few bytes against the birthday bound of an ideal hash of that width.
+
+use crate::{
+    error::HashBenchError, matrix::DEFAULT_SEED, quality::collision_limit, registry::HasherRegistry,
+};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::fmt;
+
+/// The keys hashed by a collision experiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySet {
+    /// The little endian integers `0..keys`, as used for sequential ids.
+    Sequential,
+    /// Random 16 byte keys.
+    Random,
+}
+
+impl fmt::Display for KeySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeySet::Sequential => "sequential",
+            KeySet::Random => "random",
+        })
+    }
+}
+
+/// Parameters of a collision experiment.
+#[derive(Debug, Clone)]
+pub struct CollisionConfig {
+    /// Seed of the random keys, so runs can be reproduced.
+    pub seed: u64,
+    /// Number of keys to hash.
+    pub keys: usize,
+    /// The keys to hash.
+    pub key_set: KeySet,
+    /// Widths in bits, from 1 to 64, to which the digests are truncated.
+    pub widths: Vec<u32>,
+}
+
+impl Default for CollisionConfig {
+    /// A million random keys, truncated to 24, 32, 40, 48 and 64 bits.
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_SEED,
+            keys: 1 << 20,
+            key_set: KeySet::Random,
+            widths: vec![24, 32, 40, 48, 64],
+        }
+    }
+}
+
+/// The collisions among a set of digests truncated to a single width.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionReport {
+    /// Name of the algorithm in the [`HasherRegistry`].
+    pub algorithm: String,
+    /// Number of keys which were hashed.
+    pub keys: usize,
+    /// Width in bits of the truncated digests.
+    pub width: u32,
+    /// Number of keys whose truncated digest equals that of an earlier key.
+    pub observed: usize,
+    /// Number of such keys expected of an ideal hash.
+    pub expected: f64,
+}
+
+impl CollisionReport {
+    /// The highest number of collisions an ideal hash is expected to produce.
+    pub fn limit(&self) -> f64 {
+        collision_limit(self.expected)
+    }
+
+    /// Whether the observed collisions are within the limit.
+    pub fn passed(&self) -> bool {
+        self.observed as f64 <= self.limit()
+    }
+
+    /// The number of keys at which an ideal hash of this width has a 50% chance of a collision.
+    pub fn birthday_bound(&self) -> f64 {
+        (2.0 * 2f64.ln() * 2f64.powi(self.width as i32)).sqrt()
+    }
+}
+
+impl fmt::Display for CollisionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} truncated to {} bits: {} collisions among {} keys, expected {:.2} (limit {}, birthday bound {:.0} keys) {}",
+            self.algorithm,
+            self.width,
+            self.observed,
+            self.keys,
+            self.expected,
+            self.limit(),
+            self.birthday_bound(),
+            if self.passed() { "pass" } else { "FAIL" }
+        )
+    }
+}
+
+/// The number of keys among `keys` expected to have the same value as an earlier key, when
+/// drawn uniformly from `2^width` values.
+pub fn expected_collisions(keys: usize, width: u32) -> f64 {
+    let n = keys as f64;
+    let m = 2f64.powi(width as i32);
+    if n * n < m {
+        // Far below the birthday bound the closed form below cancels out, so use the leading
+        // terms of its expansion instead.
+        let pairs = n * (n - 1.0) / 2.0;
+        let triples = pairs * (n - 2.0) / 3.0;
+        return pairs / m - triples / (m * m);
+    }
+    // n minus the expected number of distinct values, m * (1 - (1 - 1/m)^n).
+    n + m * (n * (-1.0 / m).ln_1p()).exp_m1()
+}
+
+/// Hash the keys of `config` with the algorithm registered as `name`, and count the collisions
+/// of the digests truncated to each of the configured widths.
+pub fn collisions(
+    registry: &HasherRegistry,
+    name: &str,
+    config: &CollisionConfig,
+) -> Result<Vec<CollisionReport>, HashBenchError> {
+    if !registry.contains(name) {
+        return Err(HashBenchError::UnknownAlgorithm(name.into()));
+    }
+    let Some(widest) = config.widths.iter().copied().max() else {
+        return Ok(Vec::new());
+    };
+    if let Some(&width) = config.widths.iter().find(|&&w| w == 0 || w > 64) {
+        return Err(HashBenchError::InvalidWidth(width));
+    }
+    let prefix_len = widest.div_ceil(8) as usize;
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut random = [0; 16];
+    let mut prefixes = Vec::with_capacity(config.keys);
+    for i in 0..config.keys as u64 {
+        let key = match config.key_set {
+            KeySet::Sequential => &i.to_le_bytes()[..],
+            KeySet::Random => {
+                rng.fill_bytes(&mut random);
+                &random[..]
+            }
+        };
+        let mut hasher = registry.try_get(name)?;
+        hasher.update(key);
+        let prefix = hasher
+            .finalize_truncated(prefix_len)?
+            .iter()
+            .fold(0u64, |acc, &b| acc << 8 | b as u64);
+        // Left align, so truncating to a width keeps the leading bits of the digest.
+        prefixes.push(prefix << (64 - 8 * prefix_len as u32));
+    }
+
+    Ok(config
+        .widths
+        .iter()
+        .map(|&width| {
+            let mut truncated: Vec<_> = prefixes.iter().map(|p| p >> (64 - width)).collect();
+            truncated.sort_unstable();
+            CollisionReport {
+                algorithm: name.into(),
+                keys: config.keys,
+                width,
+                observed: truncated.windows(2).filter(|w| w[0] == w[1]).count(),
+                expected: expected_collisions(config.keys, width),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collisions, expected_collisions, CollisionConfig, KeySet};
+    use crate::{error::HashBenchError, registry::HasherRegistry};
+
+    #[test]
+    fn truncated_collisions() {
+        let registry = HasherRegistry::builtin();
+        let config = CollisionConfig {
+            keys: 4096,
+            key_set: KeySet::Sequential,
+            widths: vec![8, 12, 20, 64],
+            ..CollisionConfig::default()
+        };
+
+        let reports = collisions(&registry, "xxh3-64", &config).unwrap();
+        // Every value of a byte is taken, so all other keys collide.
+        assert_eq!(reports[0].observed, 4096 - 256);
+        for report in &reports {
+            assert!(report.passed(), "{report}");
+        }
+
+        assert_eq!(
+            collisions(&registry, "crc32", &config),
+            Err(HashBenchError::UnsupportedOutputLength {
+                requested: 8,
+                supported: 4
+            })
+        );
+        for width in [0, 65] {
+            let config = CollisionConfig {
+                widths: vec![32, width],
+                ..config.clone()
+            };
+            assert_eq!(
+                collisions(&registry, "xxh3-64", &config),
+                Err(HashBenchError::InvalidWidth(width))
+            );
+        }
+    }
+
+    #[test]
+    fn birthday_approximation() {
+        // Far below the birthday bound, the expected collisions approach n^2 / 2m.
+        let expected = expected_collisions(1 << 10, 64);
+        assert!((expected / (1024.0 * 1023.0 / 2.0) * 2f64.powi(64) - 1.0).abs() < 1e-9);
+        // Far above it, nearly every value is taken.
+        assert!((expected_collisions(1 << 20, 8) - ((1 << 20) - 256) as f64).abs() < 1e-6);
+    }
+}
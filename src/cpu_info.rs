@@ -0,0 +1,316 @@
+//! The CPU a benchmark runs on: its model, cores, caches and the instruction set extensions which
+//! the hashers select their implementation by, without which throughput numbers can't be compared.
+
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs, thread,
+};
+
+/// A cache of the CPU.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Cache {
+    /// Level of the cache, starting at 1.
+    pub level: u8,
+    /// What the cache holds: "data", "instruction" or "unified".
+    pub kind: &'static str,
+    /// Size of the cache in bytes.
+    pub size: usize,
+}
+
+impl fmt::Display for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.kind {
+            "data" => "d",
+            "instruction" => "i",
+            _ => "",
+        };
+        write!(f, "L{}{suffix} ", self.level)?;
+        if self.size >= 1 << 20 && self.size.is_multiple_of(1 << 20) {
+            write!(f, "{} MiB", self.size >> 20)
+        } else {
+            write!(f, "{} KiB", self.size >> 10)
+        }
+    }
+}
+
+/// Description of the CPU, as reported by the operating system and cpuid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CpuInfo {
+    /// Vendor of the CPU, such as "GenuineIntel" or "AuthenticAMD".
+    pub vendor: String,
+    /// Model name of the CPU.
+    pub model: String,
+    /// Number of physical cores, if known.
+    pub physical_cores: Option<usize>,
+    /// Number of logical cores, including those of simultaneous multithreading.
+    pub logical_cores: usize,
+    /// Caches of a single core, from the smallest to the largest level.
+    pub caches: Vec<Cache>,
+    /// Whether each of the instruction set extensions relevant to the hashers is available at
+    /// runtime, by the name used in `is_x86_feature_detected!` or `is_aarch64_feature_detected!`.
+    pub features: BTreeMap<&'static str, bool>,
+}
+
+impl CpuInfo {
+    /// Detect the CPU this process runs on.
+    pub fn detect() -> Self {
+        let proc = fs::read_to_string("/proc/cpuinfo")
+            .map(|info| ProcCpuInfo::parse(&info))
+            .unwrap_or_default();
+        let cpuid = cpuid::detect();
+        Self {
+            vendor: proc
+                .vendor
+                .or(cpuid.vendor)
+                .unwrap_or_else(|| "unknown".into()),
+            model: proc
+                .model
+                .or(cpuid.brand)
+                .unwrap_or_else(|| "unknown".into()),
+            physical_cores: proc.physical_cores,
+            logical_cores: proc
+                .logical_cores
+                .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+                .unwrap_or(1),
+            caches: cpuid.caches,
+            features: features(),
+        }
+    }
+}
+
+impl fmt::Display for CpuInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cpu: {} ({})", self.model, self.vendor)?;
+        match self.physical_cores {
+            Some(physical) => writeln!(
+                f,
+                "cores: {physical} physical, {} logical",
+                self.logical_cores
+            )?,
+            None => writeln!(f, "cores: {} logical", self.logical_cores)?,
+        }
+        f.write_str("caches:")?;
+        if self.caches.is_empty() {
+            f.write_str(" unknown")?;
+        }
+        for (i, cache) in self.caches.iter().enumerate() {
+            write!(f, "{} {cache}", if i == 0 { "" } else { "," })?;
+        }
+        f.write_str("\nfeatures:")?;
+        for (feature, &available) in &self.features {
+            write!(f, " {}{feature}", if available { '+' } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+/// The fields of /proc/cpuinfo this module uses.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ProcCpuInfo {
+    vendor: Option<String>,
+    model: Option<String>,
+    physical_cores: Option<usize>,
+    logical_cores: Option<usize>,
+}
+
+impl ProcCpuInfo {
+    /// Parse the contents of /proc/cpuinfo, which has a block of `key : value` lines per logical
+    /// core.
+    fn parse(info: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut logical = 0;
+        // Physical cores are identified by their package and core id.
+        let mut cores = BTreeSet::new();
+        let mut package = None;
+        for (key, value) in info.lines().filter_map(|l| l.split_once(':')) {
+            let value = value.trim();
+            match key.trim() {
+                "processor" => logical += 1,
+                "vendor_id" if parsed.vendor.is_none() => parsed.vendor = Some(value.into()),
+                "model name" if parsed.model.is_none() => parsed.model = Some(value.into()),
+                "physical id" => package = Some(value.to_owned()),
+                "core id" => {
+                    cores.insert((package.clone(), value.to_owned()));
+                }
+                _ => {}
+            }
+        }
+        parsed.logical_cores = (logical > 0).then_some(logical);
+        parsed.physical_cores = (!cores.is_empty()).then_some(cores.len());
+        parsed
+    }
+}
+
+/// The extensions relevant to the hashers: SSE4.2 for CRC32C, PCLMULQDQ for the CRC folding, AVX2
+/// and AVX-512 for BLAKE2 and BLAKE3 and SHA-NI for SHA-256, with VAES and VPCLMULQDQ as their
+/// 256 and 512 bit variants.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn features() -> BTreeMap<&'static str, bool> {
+    BTreeMap::from([
+        ("sse2", is_x86_feature_detected!("sse2")),
+        ("sse4.1", is_x86_feature_detected!("sse4.1")),
+        ("sse4.2", is_x86_feature_detected!("sse4.2")),
+        ("pclmulqdq", is_x86_feature_detected!("pclmulqdq")),
+        ("avx2", is_x86_feature_detected!("avx2")),
+        ("avx512f", is_x86_feature_detected!("avx512f")),
+        ("avx512vl", is_x86_feature_detected!("avx512vl")),
+        ("sha", is_x86_feature_detected!("sha")),
+        ("vaes", is_x86_feature_detected!("vaes")),
+        ("vpclmulqdq", is_x86_feature_detected!("vpclmulqdq")),
+    ])
+}
+
+/// The AArch64 counterparts of the x86 extensions: NEON, the CRC32 instructions, PMULL for
+/// carry-less multiplication, and the SHA and AES extensions.
+#[cfg(target_arch = "aarch64")]
+fn features() -> BTreeMap<&'static str, bool> {
+    use std::arch::is_aarch64_feature_detected;
+
+    BTreeMap::from([
+        ("neon", is_aarch64_feature_detected!("neon")),
+        ("crc", is_aarch64_feature_detected!("crc")),
+        ("pmull", is_aarch64_feature_detected!("pmull")),
+        ("sha2", is_aarch64_feature_detected!("sha2")),
+        ("sha3", is_aarch64_feature_detected!("sha3")),
+        ("aes", is_aarch64_feature_detected!("aes")),
+    ])
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn features() -> BTreeMap<&'static str, bool> {
+    BTreeMap::new()
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod cpuid {
+    use super::Cache;
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::{__cpuid, __cpuid_count, CpuidResult};
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::{__cpuid, __cpuid_count, CpuidResult};
+
+    /// What cpuid reports about the CPU.
+    #[derive(Debug, Default)]
+    pub(super) struct Cpuid {
+        pub(super) vendor: Option<String>,
+        pub(super) brand: Option<String>,
+        pub(super) caches: Vec<Cache>,
+    }
+
+    fn bytes(registers: &[u32]) -> String {
+        let bytes: Vec<u8> = registers.iter().flat_map(|r| r.to_le_bytes()).collect();
+        String::from_utf8_lossy(&bytes)
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_owned()
+    }
+
+    // The cpuid intrinsics were unsafe functions up to recent Rust releases, which the
+    // `rust-version` of this crate predates.
+    #[allow(unused_unsafe)]
+    pub(super) fn detect() -> Cpuid {
+        // SAFETY: leaf 0 is supported by every CPU with cpuid.
+        let CpuidResult {
+            eax: max_leaf,
+            ebx,
+            ecx,
+            edx,
+        } = unsafe { __cpuid(0) };
+        let vendor = bytes(&[ebx, edx, ecx]);
+        // SAFETY: the extended leaves are reserved on every CPU, and report the maximum extended
+        // leaf.
+        let max_extended_leaf = unsafe { __cpuid(0x8000_0000) }.eax;
+
+        let brand = (max_extended_leaf >= 0x8000_0004).then(|| {
+            let registers: Vec<u32> = (0x8000_0002..=0x8000_0004)
+                // SAFETY: these leaves are at most the reported maximum extended leaf.
+                .map(|leaf| unsafe { __cpuid(leaf) })
+                .flat_map(|r| [r.eax, r.ebx, r.ecx, r.edx])
+                .collect();
+            bytes(&registers)
+        });
+
+        // Intel and AMD describe their caches with the same layout, in different leaves.
+        let cache_leaf = if vendor == "AuthenticAMD" || vendor == "HygonGenuine" {
+            (max_extended_leaf >= 0x8000_001d).then_some(0x8000_001d)
+        } else {
+            (max_leaf >= 4).then_some(4)
+        };
+        let mut caches = Vec::new();
+        if let Some(leaf) = cache_leaf {
+            for subleaf in 0.. {
+                // SAFETY: the leaf is at most the reported maximum, and every subleaf is valid
+                // until one reports no cache.
+                let r = unsafe { __cpuid_count(leaf, subleaf) };
+                let kind = match r.eax & 0x1f {
+                    0 => break,
+                    1 => "data",
+                    2 => "instruction",
+                    3 => "unified",
+                    _ => continue,
+                };
+                let ways = (r.ebx >> 22) as usize + 1;
+                let partitions = (r.ebx >> 12 & 0x3ff) as usize + 1;
+                let line = (r.ebx & 0xfff) as usize + 1;
+                let sets = r.ecx as usize + 1;
+                caches.push(Cache {
+                    level: (r.eax >> 5 & 0x7) as u8,
+                    kind,
+                    size: ways * partitions * line * sets,
+                });
+            }
+        }
+
+        Cpuid {
+            vendor: Some(vendor),
+            brand,
+            caches,
+        }
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+mod cpuid {
+    use super::Cache;
+
+    #[derive(Debug, Default)]
+    pub(super) struct Cpuid {
+        pub(super) vendor: Option<String>,
+        pub(super) brand: Option<String>,
+        pub(super) caches: Vec<Cache>,
+    }
+
+    /// Other architectures have no cpuid, so everything comes from the operating system.
+    pub(super) fn detect() -> Cpuid {
+        Cpuid::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CpuInfo, ProcCpuInfo};
+
+    #[test]
+    fn parse_proc_cpuinfo() {
+        let mut info = String::new();
+        for (processor, core) in [(0, 0), (1, 1), (2, 0), (3, 1)] {
+            info.push_str(&format!(
+                "processor\t: {processor}\nvendor_id\t: GenuineIntel\nmodel name\t: Test CPU @ 3.00GHz\nphysical id\t: 0\ncore id\t\t: {core}\n\n"
+            ));
+        }
+        let parsed = ProcCpuInfo::parse(&info);
+        assert_eq!(parsed.vendor.as_deref(), Some("GenuineIntel"));
+        assert_eq!(parsed.model.as_deref(), Some("Test CPU @ 3.00GHz"));
+        assert_eq!(parsed.logical_cores, Some(4));
+        assert_eq!(parsed.physical_cores, Some(2));
+        assert_eq!(ProcCpuInfo::parse(""), ProcCpuInfo::default());
+
+        let cpu = CpuInfo::detect();
+        assert!(cpu.logical_cores >= 1);
+        #[cfg(target_arch = "x86_64")]
+        assert_eq!(cpu.features.get("sse2"), Some(&true));
+        for pair in cpu.caches.windows(2) {
+            assert!(pair[0].level <= pair[1].level);
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! Cycles per byte and instructions per cycle, the frequency independent measures used in the
+//! hash literature, counted with the CPU performance counters.
+//!
+//! Core cycles and retired instructions are read through `perf_event_open` on Linux. Where that
+//! is not permitted, as in many containers and virtual machines, the time stamp counter is read
+//! instead, which counts reference cycles at a constant rate regardless of the actual core clock
+//! and doesn't count instructions.
+//!
+//! The performance counters only count the calling thread, while the time stamp counter counts
+//! wall-clock time, so the two can't be compared for hashers which use multiple threads. Those
+//! are only in [`HasherRegistry::parallel`].
+
+use crate::{error::HashBenchError, registry::HasherRegistry};
+use serde::Serialize;
+use std::{fmt, io};
+
+/// Number of samples of which the median is reported.
+const SAMPLES: usize = 21;
+
+/// Every sample runs enough iterations to take at least this many cycles, so the cost of reading
+/// the counters doesn't matter.
+const MIN_SAMPLE_CYCLES: u64 = 1 << 22;
+
+/// Where cycle counts come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CounterSource {
+    /// Core cycles and retired instructions in user space, from `perf_event_open`.
+    Perf,
+    /// Reference cycles of the time stamp counter.
+    Rdtsc,
+}
+
+impl fmt::Display for CounterSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CounterSource::Perf => "perf_event_open core cycles",
+            CounterSource::Rdtsc => "rdtsc reference cycles",
+        })
+    }
+}
+
+/// Counter values, or the difference between two of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// CPU cycles.
+    pub cycles: u64,
+    /// Retired instructions, if counted.
+    pub instructions: Option<u64>,
+}
+
+/// Reads the cycle counter of the CPU, and the instruction counter where available.
+#[derive(Debug)]
+pub struct CycleCounter {
+    #[cfg(target_os = "linux")]
+    perf: Option<perf::Group>,
+}
+
+impl CycleCounter {
+    /// The best available counter: `perf_event_open` if permitted, else the time stamp counter.
+    /// Returns `None` if neither is available on this platform.
+    pub fn new() -> Option<Self> {
+        Self::perf().ok().or_else(Self::rdtsc)
+    }
+
+    /// A counter of core cycles and retired instructions, using `perf_event_open`.
+    pub fn perf() -> io::Result<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            Ok(Self {
+                perf: Some(perf::Group::open()?),
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+
+    /// A counter of the time stamp counter, available on every x86 CPU.
+    pub fn rdtsc() -> Option<Self> {
+        cfg!(any(target_arch = "x86", target_arch = "x86_64")).then_some(Self {
+            #[cfg(target_os = "linux")]
+            perf: None,
+        })
+    }
+
+    /// Where the counts come from.
+    pub fn source(&self) -> CounterSource {
+        #[cfg(target_os = "linux")]
+        if self.perf.is_some() {
+            return CounterSource::Perf;
+        }
+        CounterSource::Rdtsc
+    }
+
+    /// The current counter values.
+    pub fn read(&self) -> Counts {
+        #[cfg(target_os = "linux")]
+        if let Some(perf) = &self.perf {
+            return perf.read();
+        }
+        Counts {
+            cycles: rdtsc(),
+            instructions: None,
+        }
+    }
+
+    /// The counts of running `f` `iterations` times.
+    pub fn measure(&self, iterations: u64, mut f: impl FnMut()) -> Counts {
+        #[cfg(target_os = "linux")]
+        if let Some(perf) = &self.perf {
+            let start = perf.read_raw();
+            for _ in 0..iterations {
+                f();
+            }
+            return perf::scale(&start, &perf.read_raw());
+        }
+        let start = self.read();
+        for _ in 0..iterations {
+            f();
+        }
+        let end = self.read();
+        Counts {
+            cycles: end.cycles.wrapping_sub(start.cycles),
+            instructions: end
+                .instructions
+                .zip(start.instructions)
+                .map(|(end, start)| end.wrapping_sub(start)),
+        }
+    }
+}
+
+#[cfg(target_arch = "x86")]
+fn rdtsc() -> u64 {
+    // SAFETY: every x86 CPU able to run Rust has the time stamp counter and lfence.
+    unsafe {
+        std::arch::x86::_mm_lfence();
+        std::arch::x86::_rdtsc()
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn rdtsc() -> u64 {
+    // SAFETY: the time stamp counter and lfence are part of the x86_64 baseline.
+    unsafe {
+        std::arch::x86_64::_mm_lfence();
+        std::arch::x86_64::_rdtsc()
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn rdtsc() -> u64 {
+    unreachable!("CycleCounter::rdtsc only succeeds on x86")
+}
+
+#[cfg(target_os = "linux")]
+mod perf {
+    use super::Counts;
+    use std::{
+        fs::File,
+        io::{self, Read},
+        os::fd::{AsRawFd, FromRawFd},
+    };
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+    const PERF_ATTR_FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const PERF_ATTR_FLAG_EXCLUDE_HV: u64 = 1 << 6;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    /// The first version of `struct perf_event_attr`, which every kernel accepts.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    /// Cycles and instructions of the calling thread, counted as a group so they cover exactly
+    /// the same code.
+    #[derive(Debug)]
+    pub(super) struct Group {
+        cycles: File,
+        _instructions: File,
+    }
+
+    fn open(config: u64, group: Option<&File>) -> io::Result<File> {
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_GROUP
+                | PERF_FORMAT_TOTAL_TIME_ENABLED
+                | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            // Unprivileged processes may only count user space.
+            flags: PERF_ATTR_FLAG_EXCLUDE_KERNEL | PERF_ATTR_FLAG_EXCLUDE_HV,
+            ..PerfEventAttr::default()
+        };
+        let group_fd = group.map_or(-1, |g| g.as_raw_fd());
+        // SAFETY: attr is a valid perf_event_attr of the size it declares, and outlives the call.
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                group_fd as libc::c_int,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel returned a new file descriptor, owned by nothing else.
+        Ok(unsafe { File::from_raw_fd(fd as libc::c_int) })
+    }
+
+    /// Raw counter values, with the time the group was enabled and the time it was actually
+    /// counting, which is less when the kernel multiplexes more events than the PMU has counters.
+    #[derive(Debug, Default)]
+    pub(super) struct Reading {
+        enabled: u64,
+        running: u64,
+        cycles: u64,
+        instructions: u64,
+    }
+
+    /// The counts between two readings, extrapolated to the whole time the group was enabled.
+    pub(super) fn scale(start: &Reading, end: &Reading) -> Counts {
+        let enabled = end.enabled.wrapping_sub(start.enabled);
+        let running = end.running.wrapping_sub(start.running);
+        let scale = |count: u64| {
+            if running == 0 {
+                return 0;
+            }
+            (count as u128 * enabled as u128 / running as u128) as u64
+        };
+        Counts {
+            cycles: scale(end.cycles.wrapping_sub(start.cycles)),
+            instructions: Some(scale(end.instructions.wrapping_sub(start.instructions))),
+        }
+    }
+
+    impl Group {
+        pub(super) fn open() -> io::Result<Self> {
+            let cycles = open(PERF_COUNT_HW_CPU_CYCLES, None)?;
+            let instructions = open(PERF_COUNT_HW_INSTRUCTIONS, Some(&cycles))?;
+            Ok(Self {
+                cycles,
+                _instructions: instructions,
+            })
+        }
+
+        pub(super) fn read_raw(&self) -> Reading {
+            // The number of counters, the enabled and running times, and the value of each counter.
+            let mut buf = [0; 5 * 8];
+            (&self.cycles)
+                .read_exact(&mut buf)
+                .expect("perf counters are readable");
+            let value = |i: usize| u64::from_ne_bytes(buf[i * 8..i * 8 + 8].try_into().unwrap());
+            Reading {
+                enabled: value(1),
+                running: value(2),
+                cycles: value(3),
+                instructions: value(4),
+            }
+        }
+
+        pub(super) fn read(&self) -> Counts {
+            scale(&Reading::default(), &self.read_raw())
+        }
+    }
+}
+
+/// Cycles and instructions spent hashing an input with an algorithm.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CyclesReport {
+    /// Name of the algorithm in the [`HasherRegistry`].
+    pub algorithm: String,
+    /// Size of the pieces in which the input was fed to the hasher.
+    pub chunk_size: usize,
+    /// Size of the input in bytes.
+    pub bytes: usize,
+    /// Where the cycles were counted.
+    pub source: CounterSource,
+    /// Median of the cycles taken to hash the input.
+    pub cycles: f64,
+    /// Instructions retired in the sample of the median cycles, if counted.
+    pub instructions: Option<f64>,
+}
+
+impl CyclesReport {
+    /// Cycles per byte of input.
+    pub fn cycles_per_byte(&self) -> f64 {
+        self.cycles / self.bytes as f64
+    }
+
+    /// Instructions per cycle, if instructions were counted.
+    pub fn instructions_per_cycle(&self) -> Option<f64> {
+        self.instructions.map(|i| i / self.cycles)
+    }
+}
+
+impl fmt::Display for CyclesReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} over {} bytes in chunks of {}: {:.3} cpb",
+            self.algorithm,
+            self.bytes,
+            self.chunk_size,
+            self.cycles_per_byte()
+        )?;
+        if let Some(ipc) = self.instructions_per_cycle() {
+            write!(f, ", {ipc:.2} IPC")?;
+        }
+        Ok(())
+    }
+}
+
+/// Count the cycles of hashing `data` in pieces of `chunk_size` bytes with the algorithm
+/// registered as `name`, reporting the median of a number of samples.
+pub fn cycles_per_byte(
+    counter: &CycleCounter,
+    registry: &HasherRegistry,
+    name: &str,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<CyclesReport, HashBenchError> {
+    if chunk_size == 0 {
+        return Err(HashBenchError::InvalidChunkSize);
+    }
+    registry.try_get(name)?;
+    let mut hash = || {
+        let hasher = registry.get(name).unwrap();
+        std::hint::black_box(hasher.hash(std::hint::black_box(data), chunk_size));
+    };
+
+    // Warm up the caches and branch predictors, and find how many iterations a sample needs.
+    let warm_up = counter.measure(1, &mut hash);
+    let iterations = MIN_SAMPLE_CYCLES.div_ceil(warm_up.cycles.max(1));
+    let mut samples: Vec<Counts> = (0..SAMPLES)
+        .map(|_| counter.measure(iterations, &mut hash))
+        .collect();
+    samples.sort_unstable_by_key(|s| s.cycles);
+    let median = samples[SAMPLES / 2];
+
+    Ok(CyclesReport {
+        algorithm: name.into(),
+        chunk_size,
+        bytes: data.len(),
+        source: counter.source(),
+        cycles: median.cycles as f64 / iterations as f64,
+        instructions: median.instructions.map(|i| i as f64 / iterations as f64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cycles_per_byte, CycleCounter};
+    use crate::{error::HashBenchError, registry::HasherRegistry};
+
+    #[test]
+    fn counts_cycles() {
+        let Some(counter) = CycleCounter::new() else {
+            return;
+        };
+        let registry = HasherRegistry::builtin();
+        let data = vec![1; 64 << 10];
+
+        let report = cycles_per_byte(&counter, &registry, "xxh3-64", &data, 4096).unwrap();
+        assert_eq!(report.bytes, data.len());
+        assert!(report.cycles_per_byte() > 0.0, "{report}");
+        assert_eq!(
+            report.instructions.is_some(),
+            counter.read().instructions.is_some()
+        );
+
+        assert_eq!(
+            cycles_per_byte(&counter, &registry, "xxh3-64", &data, 0),
+            Err(HashBenchError::InvalidChunkSize)
+        );
+        assert_eq!(
+            cycles_per_byte(&counter, &registry, "sha1", &data, 1),
+            Err(HashBenchError::UnknownAlgorithm("sha1".into()))
+        );
+    }
+}
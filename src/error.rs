@@ -0,0 +1,61 @@
+//! The error type of this crate.
+
+use std::{fmt, path::PathBuf};
+
+/// Errors reported for invalid parameters, instead of panicking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashBenchError {
+    /// Input can't be split in chunks of 0 bytes.
+    InvalidChunkSize,
+    /// A digest of `requested` bytes was asked from an algorithm producing `supported` bytes.
+    UnsupportedOutputLength { requested: usize, supported: usize },
+    /// No algorithm is registered under this name.
+    UnknownAlgorithm(String),
+    /// A dimension of the benchmark matrix has no values, so nothing would be benchmarked.
+    EmptyDimension(&'static str),
+    /// A key, salt or personalization string is too long for the algorithm.
+    InvalidKeyLength,
+    /// Work can't be split over 0 threads.
+    InvalidThreadCount,
+    /// No data pattern has this name.
+    UnknownPattern(String),
+    /// Digests can't be truncated to this number of bits, which must be from 1 to 64.
+    InvalidWidth(u32),
+    /// A seed is not a 64 bit unsigned integer.
+    InvalidSeed(String),
+    /// A corpus file can't be used as input.
+    Corpus { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for HashBenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashBenchError::InvalidChunkSize => f.write_str("chunk size must be at least 1 byte"),
+            HashBenchError::UnsupportedOutputLength {
+                requested,
+                supported,
+            } => write!(
+                f,
+                "requested a {requested} byte digest from an algorithm producing {supported} bytes"
+            ),
+            HashBenchError::UnknownAlgorithm(name) => write!(f, "unknown algorithm {name}"),
+            HashBenchError::EmptyDimension(dimension) => {
+                write!(f, "benchmark matrix has no {dimension}")
+            }
+            HashBenchError::InvalidKeyLength => {
+                f.write_str("key, salt or personalization string is too long")
+            }
+            HashBenchError::InvalidThreadCount => f.write_str("thread count must be at least 1"),
+            HashBenchError::UnknownPattern(name) => write!(f, "unknown data pattern {name}"),
+            HashBenchError::InvalidWidth(width) => {
+                write!(f, "can't truncate digests to {width} bits")
+            }
+            HashBenchError::InvalidSeed(seed) => write!(f, "invalid seed {seed}"),
+            HashBenchError::Corpus { path, reason } => {
+                write!(f, "can't read corpus {}: {reason}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for HashBenchError {}
@@ -0,0 +1,143 @@
+use super::Hasher;
+use blake2::{
+    digest::consts::{U32, U64},
+    Digest,
+};
+
+/// BLAKE2b, producing a 32 byte digest.
+pub struct Blake2Hasher32 {
+    hasher: blake2::Blake2b<U32>,
+}
+
+impl Blake2Hasher32 {
+    pub fn new() -> Self {
+        Self {
+            hasher: blake2::Blake2b::default(),
+        }
+    }
+}
+
+impl Default for Blake2Hasher32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake2Hasher32 {
+    type Output = [u8; 32];
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().into()
+    }
+
+    fn reset(&mut self) {
+        Digest::reset(&mut self.hasher);
+    }
+}
+
+/// BLAKE2b, producing a 64 byte digest.
+pub struct Blake2Hasher64 {
+    hasher: blake2::Blake2b<U64>,
+}
+
+impl Blake2Hasher64 {
+    pub fn new() -> Self {
+        Self {
+            hasher: blake2::Blake2b::default(),
+        }
+    }
+}
+
+impl Default for Blake2Hasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake2Hasher64 {
+    type Output = [u8; 64];
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().into()
+    }
+
+    fn reset(&mut self) {
+        Digest::reset(&mut self.hasher);
+    }
+}
+
+/// BLAKE2bp, hashing 4 interleaved BLAKE2b leaves in parallel with SIMD, producing a 64 byte
+/// digest.
+pub struct Blake2bpHasher64 {
+    state: blake2b_simd::blake2bp::State,
+}
+
+impl Blake2bpHasher64 {
+    pub fn new() -> Self {
+        Self {
+            state: blake2b_simd::blake2bp::State::new(),
+        }
+    }
+}
+
+impl Default for Blake2bpHasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake2bpHasher64 {
+    type Output = [u8; 64];
+    fn update(&mut self, data: &[u8]) {
+        self.state.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        *self.state.finalize().as_array()
+    }
+
+    fn reset(&mut self) {
+        self.state = blake2b_simd::blake2bp::State::new();
+    }
+}
+
+/// BLAKE2sp, hashing 8 interleaved BLAKE2s leaves in parallel with SIMD, producing a 32 byte
+/// digest.
+pub struct Blake2spHasher32 {
+    state: blake2s_simd::blake2sp::State,
+}
+
+impl Blake2spHasher32 {
+    pub fn new() -> Self {
+        Self {
+            state: blake2s_simd::blake2sp::State::new(),
+        }
+    }
+}
+
+impl Default for Blake2spHasher32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake2spHasher32 {
+    type Output = [u8; 32];
+    fn update(&mut self, data: &[u8]) {
+        self.state.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        *self.state.finalize().as_array()
+    }
+
+    fn reset(&mut self) {
+        self.state = blake2s_simd::blake2sp::State::new();
+    }
+}
@@ -0,0 +1,135 @@
+use super::Hasher;
+use std::{io, path::Path};
+
+/// BLAKE3, producing the default 32 byte digest.
+pub struct Blake3Hasher32 {
+    hasher: blake3::Hasher,
+}
+
+impl Blake3Hasher32 {
+    pub fn new() -> Self {
+        Self {
+            hasher: blake3::Hasher::new(),
+        }
+    }
+}
+
+impl Default for Blake3Hasher32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake3Hasher32 {
+    type Output = [u8; 32];
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().into()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// BLAKE3, producing the default 32 byte digest, splitting every update over the threads of the
+/// current rayon thread pool.
+///
+/// Splitting only pays off for updates of at least 128 KiB; smaller updates are slower than with
+/// [`Blake3Hasher32`].
+pub struct Blake3RayonHasher32 {
+    hasher: blake3::Hasher,
+}
+
+impl Blake3RayonHasher32 {
+    pub fn new() -> Self {
+        Self {
+            hasher: blake3::Hasher::new(),
+        }
+    }
+}
+
+impl Default for Blake3RayonHasher32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake3RayonHasher32 {
+    type Output = [u8; 32];
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update_rayon(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().into()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// Hash the file at `path` with BLAKE3, memory mapping it and splitting it over the threads of
+/// the current rayon thread pool.
+pub fn blake3_mmap_rayon(path: &Path) -> io::Result<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_mmap_rayon(path)?;
+    Ok(hasher.finalize().into())
+}
+
+/// BLAKE3, producing a 64 byte digest through the extendable output function.
+pub struct Blake3Hasher64 {
+    hasher: blake3::Hasher,
+}
+
+impl Blake3Hasher64 {
+    pub fn new() -> Self {
+        Self {
+            hasher: blake3::Hasher::new(),
+        }
+    }
+}
+
+impl Default for Blake3Hasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake3Hasher64 {
+    type Output = [u8; 64];
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        let mut output_reader = self.hasher.finalize_xof();
+        let mut output = [0; 64];
+        output_reader.fill(&mut output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::blake3_mmap_rayon;
+    use std::{env, fs, process};
+
+    #[test]
+    fn mmap_rayon_matches_hash() {
+        let data: Vec<u8> = (0..300_000).map(|i| (i % 251) as u8).collect();
+        let path = env::temp_dir().join(format!("hash_bench-mmap-{}", process::id()));
+        fs::write(&path, &data).unwrap();
+        let digest = blake3_mmap_rayon(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(digest.unwrap(), *blake3::hash(&data).as_bytes());
+    }
+}
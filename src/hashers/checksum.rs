@@ -0,0 +1,127 @@
+//! Simple additive checksums.
+
+use super::Hasher;
+
+/// Adler-32, as used by zlib, producing the checksum as 4 big endian bytes.
+#[derive(Default)]
+pub struct Adler32Hasher {
+    adler: adler::Adler32,
+}
+
+impl Adler32Hasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hasher for Adler32Hasher {
+    type Output = [u8; 4];
+
+    fn update(&mut self, data: &[u8]) {
+        self.adler.write_slice(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.adler.checksum().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.adler = adler::Adler32::new();
+    }
+}
+
+/// Fletcher-32 over little endian 16 bit words, producing the checksum as 4 big endian bytes. An
+/// input of odd length is padded with a zero byte.
+#[derive(Default)]
+pub struct Fletcher32Hasher {
+    sum1: u32,
+    sum2: u32,
+    /// Trailing byte of the previous update which did not form a full word yet.
+    pending: Option<u8>,
+}
+
+impl Fletcher32Hasher {
+    /// The maximum amount of words which can be summed before the sums must be reduced to avoid
+    /// overflowing.
+    const BLOCK_WORDS: usize = 359;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hasher for Fletcher32Hasher {
+    type Output = [u8; 4];
+
+    fn update(&mut self, mut data: &[u8]) {
+        if let Some(low) = self.pending.take() {
+            match data.split_first() {
+                Some((&high, rest)) => {
+                    self.sum1 = (self.sum1 + u16::from_le_bytes([low, high]) as u32) % 65535;
+                    self.sum2 = (self.sum2 + self.sum1) % 65535;
+                    data = rest;
+                }
+                None => {
+                    self.pending = Some(low);
+                    return;
+                }
+            }
+        }
+
+        let (words, rest) = data.split_at(data.len() & !1);
+        for block in words.chunks(2 * Self::BLOCK_WORDS) {
+            for word in block.chunks_exact(2) {
+                self.sum1 += u16::from_le_bytes([word[0], word[1]]) as u32;
+                self.sum2 += self.sum1;
+            }
+            self.sum1 %= 65535;
+            self.sum2 %= 65535;
+        }
+        self.pending = rest.first().copied();
+    }
+
+    fn finalize(mut self) -> Self::Output {
+        if self.pending.is_some() {
+            self.update(&[0]);
+        }
+        ((self.sum2 << 16) | self.sum1).to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Adler32Hasher, Fletcher32Hasher, Hasher};
+
+    #[test]
+    fn adler32_known_answers() {
+        assert_eq!(Adler32Hasher::new().hash(b"", 1), 1u32.to_be_bytes());
+        assert_eq!(
+            Adler32Hasher::new().hash(b"Wikipedia", 4),
+            0x11e60398u32.to_be_bytes()
+        );
+        assert_eq!(
+            Adler32Hasher::new().hash(b"123456789", 4),
+            0x091e01deu32.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn fletcher32_known_answers() {
+        for (input, expected) in [
+            (&b"abcde"[..], 0xf04fc729u32),
+            (b"abcdef", 0x56502d2a),
+            (b"abcdefgh", 0xebe19591),
+        ] {
+            for chunk_size in 1..=input.len() {
+                assert_eq!(
+                    Fletcher32Hasher::new().hash(input, chunk_size),
+                    expected.to_be_bytes()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Cyclic redundancy checks.
+//!
+//! Where a hardware accelerated implementation exists, a table driven (slice-by-16)
+//! implementation of the same polynomial is provided as well so the two can be compared.
+
+use super::Hasher;
+use crc::{Crc, Table, CRC_32_ISCSI, CRC_32_ISO_HDLC, CRC_64_ECMA_182, CRC_64_NVME};
+
+/// CRC-32 (IEEE 802.3), producing the checksum as 4 big endian bytes.
+pub struct Crc32Hasher {
+    hasher: crc32fast::Hasher,
+}
+
+impl Crc32Hasher {
+    pub fn new() -> Self {
+        Self {
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+}
+
+impl Default for Crc32Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Crc32Hasher {
+    type Output = [u8; 4];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// CRC-32C (Castagnoli), producing the checksum as 4 big endian bytes. Uses SSE 4.2 or the ARMv8
+/// CRC instructions if available.
+#[derive(Default)]
+pub struct Crc32cHasher {
+    crc: u32,
+}
+
+impl Crc32cHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hasher for Crc32cHasher {
+    type Output = [u8; 4];
+
+    fn update(&mut self, data: &[u8]) {
+        self.crc = crc32c::crc32c_append(self.crc, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.crc.to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.crc = 0;
+    }
+}
+
+/// CRC-64/NVME, producing the checksum as 8 big endian bytes. Uses carry-less multiplication
+/// (PCLMULQDQ, VPCLMULQDQ or PMULL) if available.
+pub struct Crc64NvmeHasher {
+    digest: crc64fast_nvme::Digest,
+}
+
+impl Crc64NvmeHasher {
+    pub fn new() -> Self {
+        Self {
+            digest: crc64fast_nvme::Digest::new(),
+        }
+    }
+}
+
+impl Default for Crc64NvmeHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Crc64NvmeHasher {
+    type Output = [u8; 8];
+
+    fn update(&mut self, data: &[u8]) {
+        self.digest.write(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.digest.sum64().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.digest = crc64fast_nvme::Digest::new();
+    }
+}
+
+/// Define a [`Hasher`] wrapper around a table driven CRC from the `crc` crate.
+macro_rules! table_crc_hasher {
+    ($(#[$attr:meta])* $name:ident, $width:ty, $algorithm:expr) => {
+        $(#[$attr])*
+        pub struct $name {
+            digest: crc::Digest<'static, $width, Table<16>>,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                static CRC: Crc<$width, Table<16>> = Crc::<$width, Table<16>>::new(&$algorithm);
+                Self {
+                    digest: CRC.digest(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Hasher for $name {
+            type Output = [u8; std::mem::size_of::<$width>()];
+
+            fn update(&mut self, data: &[u8]) {
+                self.digest.update(data);
+            }
+
+            fn finalize(self) -> Self::Output {
+                self.digest.finalize().to_be_bytes()
+            }
+
+            fn reset(&mut self) {
+                *self = Self::new();
+            }
+        }
+    };
+}
+
+table_crc_hasher!(
+    /// Table driven CRC-32 (IEEE 802.3), producing the checksum as 4 big endian bytes.
+    Crc32TableHasher,
+    u32,
+    CRC_32_ISO_HDLC
+);
+table_crc_hasher!(
+    /// Table driven CRC-32C (Castagnoli), producing the checksum as 4 big endian bytes.
+    Crc32cTableHasher,
+    u32,
+    CRC_32_ISCSI
+);
+table_crc_hasher!(
+    /// Table driven CRC-64/ECMA-182, producing the checksum as 8 big endian bytes.
+    Crc64EcmaHasher,
+    u64,
+    CRC_64_ECMA_182
+);
+table_crc_hasher!(
+    /// Table driven CRC-64/NVME, producing the checksum as 8 big endian bytes.
+    Crc64NvmeTableHasher,
+    u64,
+    CRC_64_NVME
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK_INPUT: &[u8] = b"123456789";
+
+    #[test]
+    fn catalogue_check_values() {
+        let crc32 = 0xcbf43926u32.to_be_bytes();
+        assert_eq!(Crc32Hasher::new().hash(CHECK_INPUT, 4), crc32);
+        assert_eq!(Crc32TableHasher::new().hash(CHECK_INPUT, 4), crc32);
+
+        let crc32c = 0xe3069283u32.to_be_bytes();
+        assert_eq!(Crc32cHasher::new().hash(CHECK_INPUT, 4), crc32c);
+        assert_eq!(Crc32cTableHasher::new().hash(CHECK_INPUT, 4), crc32c);
+
+        let crc64_ecma = 0x6c40df5f0b497347u64.to_be_bytes();
+        assert_eq!(Crc64EcmaHasher::new().hash(CHECK_INPUT, 4), crc64_ecma);
+
+        let crc64_nvme = 0xae8b14860a799888u64.to_be_bytes();
+        assert_eq!(Crc64NvmeHasher::new().hash(CHECK_INPUT, 4), crc64_nvme);
+        assert_eq!(Crc64NvmeTableHasher::new().hash(CHECK_INPUT, 4), crc64_nvme);
+    }
+}
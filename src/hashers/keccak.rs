@@ -0,0 +1,130 @@
+//! Keccak based hashes: SHA-3, the SHAKE extendable output functions and KangarooTwelve.
+
+use super::Hasher;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+
+digest_hasher!(
+    /// SHA3-256, producing a 32 byte digest.
+    Sha3_256Hasher,
+    sha3::Sha3_256,
+    32
+);
+digest_hasher!(
+    /// SHA3-512, producing a 64 byte digest.
+    Sha3_512Hasher,
+    sha3::Sha3_512,
+    64
+);
+
+/// SHAKE128, producing a 32 byte digest through the extendable output function.
+pub struct Shake128Hasher32 {
+    hasher: sha3::Shake128,
+}
+
+impl Shake128Hasher32 {
+    pub fn new() -> Self {
+        Self {
+            hasher: sha3::Shake128::default(),
+        }
+    }
+}
+
+impl Default for Shake128Hasher32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Shake128Hasher32 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) {
+        Update::update(&mut self.hasher, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        let mut output_reader = self.hasher.finalize_xof();
+        let mut output = [0; 32];
+        output_reader.read(&mut output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.hasher = sha3::Shake128::default();
+    }
+}
+
+/// SHAKE256, producing a 64 byte digest through the extendable output function.
+pub struct Shake256Hasher64 {
+    hasher: sha3::Shake256,
+}
+
+impl Shake256Hasher64 {
+    pub fn new() -> Self {
+        Self {
+            hasher: sha3::Shake256::default(),
+        }
+    }
+}
+
+impl Default for Shake256Hasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Shake256Hasher64 {
+    type Output = [u8; 64];
+
+    fn update(&mut self, data: &[u8]) {
+        Update::update(&mut self.hasher, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        let mut output_reader = self.hasher.finalize_xof();
+        let mut output = [0; 64];
+        output_reader.read(&mut output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.hasher = sha3::Shake256::default();
+    }
+}
+
+/// KangarooTwelve with an empty customization string, producing a 32 byte digest.
+pub struct KangarooTwelveHasher32 {
+    hasher: tiny_keccak::KangarooTwelve<&'static [u8]>,
+}
+
+impl KangarooTwelveHasher32 {
+    pub fn new() -> Self {
+        Self {
+            hasher: tiny_keccak::KangarooTwelve::new(b""),
+        }
+    }
+}
+
+impl Default for KangarooTwelveHasher32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for KangarooTwelveHasher32 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) {
+        tiny_keccak::Hasher::update(&mut self.hasher, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        let mut output = [0; 32];
+        tiny_keccak::Hasher::finalize(self.hasher, &mut output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.hasher = tiny_keccak::KangarooTwelve::new(b"");
+    }
+}
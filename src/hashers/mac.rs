@@ -0,0 +1,221 @@
+//! Keyed hashes and message authentication codes.
+
+use super::Hasher;
+use crate::error::HashBenchError;
+use blake2::digest::{
+    consts::{U32, U64},
+    Mac,
+};
+
+/// BLAKE3 in keyed mode, producing a 32 byte MAC.
+pub struct Blake3KeyedHasher32 {
+    hasher: blake3::Hasher,
+}
+
+impl Blake3KeyedHasher32 {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            hasher: blake3::Hasher::new_keyed(key),
+        }
+    }
+}
+
+impl Hasher for Blake3KeyedHasher32 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().into()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// BLAKE3 in key derivation mode, deriving a 32 byte key from the input as key material.
+pub struct Blake3DeriveKeyHasher32 {
+    hasher: blake3::Hasher,
+}
+
+impl Blake3DeriveKeyHasher32 {
+    /// Create a new hasher for the given context string, which should be hardcoded, globally
+    /// unique and application specific.
+    pub fn new(context: &str) -> Self {
+        Self {
+            hasher: blake3::Hasher::new_derive_key(context),
+        }
+    }
+}
+
+impl Hasher for Blake3DeriveKeyHasher32 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.finalize().into()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// Define a [`Hasher`] wrapper computing a keyed BLAKE2b MAC of `$len` bytes, with `$size` the
+/// matching `typenum` output size.
+macro_rules! blake2b_mac_hasher {
+    ($(#[$attr:meta])* $name:ident, $size:ty, $len:literal) => {
+        $(#[$attr])*
+        pub struct $name {
+            mac: blake2::Blake2bMac<$size>,
+            initial: blake2::Blake2bMac<$size>,
+        }
+
+        impl $name {
+            /// Create a new MAC with a key of at most 64 bytes, and a salt and personalization
+            /// string of at most 16 bytes each.
+            pub fn new(key: &[u8], salt: &[u8], persona: &[u8]) -> Result<Self, HashBenchError> {
+                // blake2 checks the key, salt and personalization string against the block size
+                // only, and panics on keys longer than 64 bytes and salts or personalization
+                // strings longer than 16 bytes.
+                if key.len() > 64 || salt.len() > 16 || persona.len() > 16 {
+                    return Err(HashBenchError::InvalidKeyLength);
+                }
+                let mac = blake2::Blake2bMac::new_with_salt_and_personal(key, salt, persona)
+                    .map_err(|_| HashBenchError::InvalidKeyLength)?;
+                Ok(Self {
+                    initial: mac.clone(),
+                    mac,
+                })
+            }
+        }
+
+        impl Hasher for $name {
+            type Output = [u8; $len];
+
+            fn update(&mut self, data: &[u8]) {
+                self.mac.update(data);
+            }
+
+            fn finalize(self) -> Self::Output {
+                self.mac.finalize().into_bytes().into()
+            }
+
+            fn reset(&mut self) {
+                self.mac = self.initial.clone();
+            }
+        }
+    };
+}
+
+blake2b_mac_hasher!(
+    /// BLAKE2b in keyed mode, producing a 32 byte MAC.
+    Blake2bMac32,
+    U32,
+    32
+);
+blake2b_mac_hasher!(
+    /// BLAKE2b in keyed mode, producing a 64 byte MAC.
+    Blake2bMac64,
+    U64,
+    64
+);
+
+/// Define a [`Hasher`] wrapper computing an HMAC with the hash `$inner`, producing a MAC of
+/// `$len` bytes.
+macro_rules! hmac_hasher {
+    ($(#[$attr:meta])* $name:ident, $inner:ty, $len:literal) => {
+        $(#[$attr])*
+        pub struct $name {
+            mac: hmac::Hmac<$inner>,
+        }
+
+        impl $name {
+            /// Create a new MAC. Keys of any length are accepted.
+            pub fn new(key: &[u8]) -> Self {
+                Self {
+                    mac: <hmac::Hmac<$inner> as Mac>::new_from_slice(key)
+                        .expect("HMAC accepts keys of any length"),
+                }
+            }
+        }
+
+        impl Hasher for $name {
+            type Output = [u8; $len];
+
+            fn update(&mut self, data: &[u8]) {
+                self.mac.update(data);
+            }
+
+            fn finalize(self) -> Self::Output {
+                self.mac.finalize().into_bytes().into()
+            }
+
+            fn reset(&mut self) {
+                Mac::reset(&mut self.mac);
+            }
+        }
+    };
+}
+
+hmac_hasher!(
+    /// HMAC-SHA256, producing a 32 byte MAC.
+    HmacSha256Hasher,
+    sha2::Sha256,
+    32
+);
+hmac_hasher!(
+    /// HMAC-SHA512, producing a 64 byte MAC.
+    HmacSha512Hasher,
+    sha2::Sha512,
+    64
+);
+hmac_hasher!(
+    /// HMAC-SHA3-256, producing a 32 byte MAC.
+    HmacSha3_256Hasher,
+    sha3::Sha3_256,
+    32
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_keeps_key() {
+        let key = [7; 32];
+        let mut mac = Blake2bMac32::new(&key, b"salt", b"persona").unwrap();
+        mac.update(b"discarded");
+        mac.reset();
+        assert_eq!(
+            mac.hash(b"message", 3),
+            Blake2bMac32::new(&key, b"salt", b"persona")
+                .unwrap()
+                .hash(b"message", 7)
+        );
+
+        let mut mac = HmacSha256Hasher::new(&key);
+        mac.update(b"discarded");
+        mac.reset();
+        assert_eq!(
+            mac.hash(b"message", 3),
+            HmacSha256Hasher::new(&key).hash(b"message", 7)
+        );
+    }
+
+    #[test]
+    fn blake2b_mac_rejects_long_parameters() {
+        assert!(Blake2bMac32::new(&[1; 64], &[0; 16], &[0; 16]).is_ok());
+        assert!(Blake2bMac64::new(&[0; 65], b"", b"").is_err());
+        assert!(Blake2bMac32::new(&[1; 32], b"", &[0; 17]).is_err());
+        assert!(Blake2bMac32::new(&[1; 32], &[0; 17], b"").is_err());
+        assert!(Blake2bMac64::new(&[1; 32], b"", &[0; 17]).is_err());
+        assert!(Blake2bMac64::new(&[1; 32], &[0; 17], b"").is_err());
+    }
+}
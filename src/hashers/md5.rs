@@ -0,0 +1,36 @@
+use super::Hasher;
+
+/// MD5, producing a 16 byte digest.
+pub struct Md5hasher {
+    ctx: md5::Context,
+}
+
+impl Md5hasher {
+    pub fn new() -> Self {
+        Self {
+            ctx: md5::Context::new(),
+        }
+    }
+}
+
+impl Default for Md5hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Md5hasher {
+    type Output = [u8; 16];
+
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.consume(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.ctx.compute().into()
+    }
+
+    fn reset(&mut self) {
+        self.ctx = md5::Context::new();
+    }
+}
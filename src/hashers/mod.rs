@@ -0,0 +1,150 @@
+//! Hasher wrappers sharing a common [`Hasher`] interface.
+
+/// Define a [`Hasher`] wrapper around a type implementing [`digest::Digest`], with a fixed size
+/// output of `$len` bytes.
+macro_rules! digest_hasher {
+    ($(#[$attr:meta])* $name:ident, $inner:ty, $len:literal) => {
+        $(#[$attr])*
+        pub struct $name {
+            hasher: $inner,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    hasher: <$inner as ::digest::Digest>::new(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $crate::hashers::Hasher for $name {
+            type Output = [u8; $len];
+
+            fn update(&mut self, data: &[u8]) {
+                ::digest::Digest::update(&mut self.hasher, data);
+            }
+
+            fn finalize(self) -> Self::Output {
+                ::digest::Digest::finalize(self.hasher).into()
+            }
+
+            fn reset(&mut self) {
+                ::digest::Digest::reset(&mut self.hasher);
+            }
+        }
+    };
+}
+
+use crate::error::HashBenchError;
+
+mod blake2;
+mod blake3;
+mod checksum;
+mod crc;
+mod keccak;
+mod mac;
+mod md5;
+mod noncrypto;
+mod sha2;
+
+pub use self::blake2::{Blake2Hasher32, Blake2Hasher64, Blake2bpHasher64, Blake2spHasher32};
+pub use self::blake3::{blake3_mmap_rayon, Blake3Hasher32, Blake3Hasher64, Blake3RayonHasher32};
+pub use self::checksum::{Adler32Hasher, Fletcher32Hasher};
+pub use self::crc::{
+    Crc32Hasher, Crc32TableHasher, Crc32cHasher, Crc32cTableHasher, Crc64EcmaHasher,
+    Crc64NvmeHasher, Crc64NvmeTableHasher,
+};
+pub use self::keccak::{
+    KangarooTwelveHasher32, Sha3_256Hasher, Sha3_512Hasher, Shake128Hasher32, Shake256Hasher64,
+};
+pub use self::mac::{
+    Blake2bMac32, Blake2bMac64, Blake3DeriveKeyHasher32, Blake3KeyedHasher32, HmacSha256Hasher,
+    HmacSha3_256Hasher, HmacSha512Hasher,
+};
+pub use self::md5::Md5hasher;
+pub use self::noncrypto::{
+    Murmur3Hasher128, Murmur3Hasher32, WyHasher, Xxh32Hasher, Xxh3Hasher128, Xxh3Hasher64,
+    Xxh64Hasher,
+};
+pub use self::sha2::{
+    sha256_accelerated, sha512_accelerated, Sha224Hasher, Sha256Hasher, Sha384Hasher, Sha512Hasher,
+    Sha512_256Hasher,
+};
+
+/// A hash function which can digest an input incrementally.
+pub trait Hasher {
+    /// The digest produced by this hasher.
+    type Output;
+
+    /// Feed `data` into the hasher.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consume the hasher and return the digest of all data fed to it so far.
+    fn finalize(self) -> Self::Output;
+
+    /// Reset the hasher to its initial state, discarding all data fed to it so far.
+    fn reset(&mut self);
+
+    /// Consume the hasher, feeding it `chunks` in pieces of `chunk_size` bytes, and return the
+    /// resulting digest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    fn hash(mut self, chunks: &[u8], chunk_size: usize) -> Self::Output
+    where
+        Self: Sized,
+    {
+        for c in chunks.chunks(chunk_size) {
+            self.update(c);
+        }
+        self.finalize()
+    }
+
+    /// Like [`hash`](Hasher::hash), but returns an error instead of panicking if `chunk_size` is
+    /// 0.
+    fn try_hash(self, chunks: &[u8], chunk_size: usize) -> Result<Self::Output, HashBenchError>
+    where
+        Self: Sized,
+    {
+        if chunk_size == 0 {
+            return Err(HashBenchError::InvalidChunkSize);
+        }
+        Ok(self.hash(chunks, chunk_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Blake3Hasher64, Hasher};
+    use crate::error::HashBenchError;
+
+    #[test]
+    fn reset_discards_state() {
+        let mut hasher = Blake3Hasher64::new();
+        hasher.update(b"some data which should be forgotten");
+        hasher.reset();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+
+        assert_eq!(
+            hasher.finalize(),
+            Blake3Hasher64::new().hash(b"hello world", 64)
+        );
+    }
+
+    #[test]
+    fn zero_chunk_size_is_an_error() {
+        assert_eq!(
+            Blake3Hasher64::new().try_hash(b"hello world", 0),
+            Err(HashBenchError::InvalidChunkSize)
+        );
+        assert!(Blake3Hasher64::new().try_hash(b"", 1).is_ok());
+    }
+}
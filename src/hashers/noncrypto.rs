@@ -0,0 +1,239 @@
+//! Fast non-cryptographic hashes: xxHash, wyhash and MurmurHash3.
+//!
+//! wyhash and MurmurHash3 do not support incremental hashing: feeding them data in pieces does
+//! not produce the same result as hashing the whole input at once. Their wrappers therefore
+//! buffer all data and hash it when finalized, so their measurements include copying the input.
+
+use super::Hasher;
+use xxhash_rust::{xxh3::Xxh3, xxh32::Xxh32, xxh64::Xxh64};
+
+/// XXH32 with seed 0, producing the hash as 4 big endian bytes.
+pub struct Xxh32Hasher {
+    hasher: Xxh32,
+}
+
+impl Xxh32Hasher {
+    pub fn new() -> Self {
+        Self {
+            hasher: Xxh32::new(0),
+        }
+    }
+}
+
+impl Default for Xxh32Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Xxh32Hasher {
+    type Output = [u8; 4];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.digest().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset(0);
+    }
+}
+
+/// XXH64 with seed 0, producing the hash as 8 big endian bytes.
+pub struct Xxh64Hasher {
+    hasher: Xxh64,
+}
+
+impl Xxh64Hasher {
+    pub fn new() -> Self {
+        Self {
+            hasher: Xxh64::new(0),
+        }
+    }
+}
+
+impl Default for Xxh64Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Xxh64Hasher {
+    type Output = [u8; 8];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.digest().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset(0);
+    }
+}
+
+/// XXH3 with the default secret, producing the 64 bit hash as 8 big endian bytes.
+pub struct Xxh3Hasher64 {
+    hasher: Xxh3,
+}
+
+impl Xxh3Hasher64 {
+    pub fn new() -> Self {
+        Self {
+            hasher: Xxh3::new(),
+        }
+    }
+}
+
+impl Default for Xxh3Hasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Xxh3Hasher64 {
+    type Output = [u8; 8];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.digest().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// XXH3 with the default secret, producing the 128 bit hash as 16 big endian bytes.
+pub struct Xxh3Hasher128 {
+    hasher: Xxh3,
+}
+
+impl Xxh3Hasher128 {
+    pub fn new() -> Self {
+        Self {
+            hasher: Xxh3::new(),
+        }
+    }
+}
+
+impl Default for Xxh3Hasher128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Xxh3Hasher128 {
+    type Output = [u8; 16];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.hasher.digest128().to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset();
+    }
+}
+
+/// wyhash with seed 0, producing the hash as 8 big endian bytes. Input is buffered until the
+/// hasher is finalized.
+#[derive(Default)]
+pub struct WyHasher {
+    buffer: Vec<u8>,
+}
+
+impl WyHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hasher for WyHasher {
+    type Output = [u8; 8];
+
+    fn update(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        wyhash::wyhash(&self.buffer, 0).to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// MurmurHash3 (x86, 32 bit) with seed 0, producing the hash as 4 big endian bytes. Input is
+/// buffered until the hasher is finalized.
+#[derive(Default)]
+pub struct Murmur3Hasher32 {
+    buffer: Vec<u8>,
+}
+
+impl Murmur3Hasher32 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hasher for Murmur3Hasher32 {
+    type Output = [u8; 4];
+
+    fn update(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        murmur3::murmur3_32(&mut self.buffer.as_slice(), 0)
+            .expect("reading from a slice can't fail")
+            .to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// MurmurHash3 (x64, 128 bit) with seed 0, producing the 16 bytes written by the reference
+/// implementation. Input is buffered until the hasher is finalized.
+#[derive(Default)]
+pub struct Murmur3Hasher128 {
+    buffer: Vec<u8>,
+}
+
+impl Murmur3Hasher128 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hasher for Murmur3Hasher128 {
+    type Output = [u8; 16];
+
+    fn update(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        murmur3::murmur3_x64_128(&mut self.buffer.as_slice(), 0)
+            .expect("reading from a slice can't fail")
+            .to_le_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
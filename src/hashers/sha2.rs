@@ -0,0 +1,80 @@
+//! The SHA-2 family.
+//!
+//! The `sha2` crate uses the SHA extensions (SHA-NI on x86, the cryptographic extension on
+//! ARMv8) when the CPU supports them. To measure the software implementation on such a CPU,
+//! save a baseline with a default build and compare it against a build with the `sha2-soft`
+//! feature enabled, e.g. `cargo bench -- --save-baseline accelerated` followed by
+//! `cargo bench --features sha2-soft -- --baseline accelerated`.
+
+digest_hasher!(
+    /// SHA-224, producing a 28 byte digest.
+    Sha224Hasher,
+    sha2::Sha224,
+    28
+);
+digest_hasher!(
+    /// SHA-256, producing a 32 byte digest.
+    Sha256Hasher,
+    sha2::Sha256,
+    32
+);
+digest_hasher!(
+    /// SHA-384, producing a 48 byte digest.
+    Sha384Hasher,
+    sha2::Sha384,
+    48
+);
+digest_hasher!(
+    /// SHA-512, producing a 64 byte digest.
+    Sha512Hasher,
+    sha2::Sha512,
+    64
+);
+digest_hasher!(
+    /// SHA-512/256, producing a 32 byte digest.
+    Sha512_256Hasher,
+    sha2::Sha512_256,
+    32
+);
+
+/// Whether SHA-224 and SHA-256 in this build use the SHA extensions of the current CPU: SHA-NI
+/// on x86, or the SHA2 extension on ARMv8.
+pub fn sha256_accelerated() -> bool {
+    if cfg!(feature = "sha2-soft") {
+        return false;
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        is_x86_feature_detected!("sha")
+            && is_x86_feature_detected!("sse2")
+            && is_x86_feature_detected!("ssse3")
+            && is_x86_feature_detected!("sse4.1")
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        std::arch::is_aarch64_feature_detected!("sha2")
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        false
+    }
+}
+
+/// Whether SHA-384, SHA-512 and SHA-512/256 in this build use the SHA extensions of the current
+/// CPU. Only ARMv8 has them, as part of its SHA3 extension. SHA-NI doesn't cover SHA-512, which
+/// `sha2` only speeds up with AVX2 on x86.
+pub fn sha512_accelerated() -> bool {
+    if cfg!(feature = "sha2-soft") {
+        return false;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        std::arch::is_aarch64_feature_detected!("sha3")
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    {
+        false
+    }
+}
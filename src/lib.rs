@@ -1,8 +1,15 @@
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        let result = 2 + 2;
-        assert_eq!(result, 4);
-    }
-}
+//! Thin wrappers around a number of hash and checksum implementations, exposing them through a
+//! common [`hashers::Hasher`] trait so they can be benchmarked and used interchangeably.
+
+pub mod batch;
+pub mod collisions;
+pub mod cpu_info;
+pub mod cycles;
+pub mod error;
+pub mod hashers;
+pub mod manifest;
+pub mod matrix;
+pub mod quality;
+pub mod registry;
+#[cfg(feature = "simd-backends")]
+pub mod simd;
@@ -0,0 +1,192 @@
+//! Run manifests, recording everything needed to reproduce and audit a benchmark run.
+
+use crate::{
+    cpu_info::CpuInfo,
+    hashers::{sha256_accelerated, sha512_accelerated},
+    matrix::{BenchMatrix, DataPattern},
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Every feature this crate declares, and whether it is enabled.
+const FEATURES: &[(&str, bool)] = &[
+    ("sha2-soft", cfg!(feature = "sha2-soft")),
+    ("simd-backends", cfg!(feature = "simd-backends")),
+];
+
+/// How a benchmark was run: its parameters, and the build and machine it ran on.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunManifest {
+    /// Name of the benchmark.
+    pub benchmark: String,
+    /// Start of the run, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Version of this crate.
+    pub crate_version: String,
+    /// Seed of the random data patterns.
+    pub seed: u64,
+    /// Names of the benchmarked algorithms.
+    pub algorithms: Vec<String>,
+    /// Benchmarks of functions which aren't in the registry, such as hashing a memory mapped file
+    /// or a single SIMD backend.
+    pub functions: Vec<String>,
+    /// Sizes of the pieces in which the input is fed to the hashers.
+    pub chunk_sizes: Vec<usize>,
+    /// Total size of the input hashed in pieces of every chunk size.
+    pub chunked_input_size: usize,
+    /// Total sizes of the inputs hashed in a single piece.
+    pub input_sizes: Vec<usize>,
+    /// Data patterns, in the form accepted by `HASH_BENCH_PATTERNS`.
+    pub patterns: Vec<String>,
+    /// Numbers of threads used by the multi-threaded hashers.
+    pub thread_counts: Vec<usize>,
+    /// Exact versions of the dependencies, from the lock file, or their version requirements if
+    /// the crate was built without one.
+    pub dependencies: BTreeMap<String, String>,
+    /// Version of the compiler.
+    pub rustc: String,
+    /// Target triple.
+    pub target: String,
+    /// Cargo profile.
+    pub profile: String,
+    /// Target features enabled at compile time.
+    pub target_features: Vec<String>,
+    /// Enabled features of this crate.
+    pub features: Vec<String>,
+    /// Whether SHA-224 and SHA-256 used the SHA extensions of the CPU.
+    pub sha256_accelerated: bool,
+    /// Whether SHA-384, SHA-512 and SHA-512/256 used the SHA extensions of the CPU.
+    pub sha512_accelerated: bool,
+    /// The CPU the benchmark ran on.
+    pub cpu: CpuInfo,
+}
+
+impl RunManifest {
+    /// Describe a run of `benchmark` over `matrix`, on this machine and build.
+    pub fn new(benchmark: &str, matrix: &BenchMatrix) -> Self {
+        let split = |s: &'static str| {
+            s.split(',')
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        Self {
+            benchmark: benchmark.into(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            crate_version: env!("CARGO_PKG_VERSION").into(),
+            seed: matrix.seed,
+            algorithms: matrix.algorithms.clone(),
+            functions: Vec::new(),
+            chunk_sizes: matrix.chunk_sizes.clone(),
+            chunked_input_size: matrix.chunked_input_size,
+            input_sizes: matrix.input_sizes.clone(),
+            patterns: matrix
+                .patterns
+                .iter()
+                .map(|p| match p {
+                    DataPattern::Corpus(path) => format!("corpus:{}", path.display()),
+                    p => p.to_string(),
+                })
+                .collect(),
+            thread_counts: matrix.thread_counts.clone(),
+            dependencies: split(env!("HASH_BENCH_DEPENDENCIES"))
+                .into_iter()
+                .filter_map(|d| {
+                    let (name, version) = d.split_once('=')?;
+                    Some((name.into(), version.into()))
+                })
+                .collect(),
+            rustc: env!("HASH_BENCH_RUSTC_VERSION").into(),
+            target: env!("HASH_BENCH_TARGET").into(),
+            profile: env!("HASH_BENCH_PROFILE").into(),
+            target_features: split(env!("HASH_BENCH_TARGET_FEATURES")),
+            features: FEATURES
+                .iter()
+                .filter(|&&(_, enabled)| enabled)
+                .map(|&(name, _)| name.into())
+                .collect(),
+            sha256_accelerated: sha256_accelerated(),
+            sha512_accelerated: sha512_accelerated(),
+            cpu: CpuInfo::detect(),
+        }
+    }
+
+    /// The manifest as pretty printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("manifest is serializable")
+    }
+
+    /// Write the manifest to `<benchmark>-manifest.json` in `dir`, and return the path of the
+    /// file.
+    pub fn write(&self, dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}-manifest.json", self.benchmark));
+        fs::write(&path, self.to_json())?;
+        Ok(path)
+    }
+}
+
+/// The directory in which criterion stores its results, next to which manifests are written.
+pub fn criterion_dir() -> PathBuf {
+    if let Some(home) = env::var_os("CRITERION_HOME") {
+        return home.into();
+    }
+    env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| PathBuf::from("target"), PathBuf::from)
+        .join("criterion")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunManifest, FEATURES};
+    use crate::{
+        hashers::{sha256_accelerated, sha512_accelerated},
+        matrix::{BenchMatrix, DataPattern},
+        registry::HasherRegistry,
+    };
+
+    #[test]
+    fn manifest_records_run() {
+        let mut matrix = BenchMatrix::new(&HasherRegistry::builtin());
+        matrix.seed = 42;
+        matrix
+            .patterns
+            .push(DataPattern::Corpus("corpus/words.txt".into()));
+        let manifest = RunManifest::new("hash_bench", &matrix);
+
+        let json: serde_json::Value = serde_json::from_str(&manifest.to_json()).unwrap();
+        assert_eq!(json["seed"], 42);
+        assert_eq!(json["patterns"][1], "corpus:corpus/words.txt");
+        assert_eq!(json["algorithms"][0], "md5");
+        assert!(json["dependencies"]["blake3"]
+            .as_str()
+            .unwrap()
+            .starts_with("1."));
+        assert!(json["rustc"].as_str().unwrap().starts_with("rustc "));
+        assert!(json["cpu"]["logical_cores"].as_u64().unwrap() >= 1);
+        assert_eq!(json["sha256_accelerated"], sha256_accelerated());
+        assert_eq!(json["sha512_accelerated"], sha512_accelerated());
+    }
+
+    #[test]
+    fn features_are_declared() {
+        let manifest = include_str!("../Cargo.toml");
+        let declared: Vec<_> = manifest
+            .lines()
+            .skip_while(|l| l.trim() != "[features]")
+            .skip(1)
+            .take_while(|l| !l.starts_with('['))
+            .filter_map(|l| l.split_once('=').map(|(name, _)| name.trim()))
+            .filter(|name| !name.starts_with('#'))
+            .collect();
+        let recorded: Vec<_> = FEATURES.iter().map(|&(name, _)| name).collect();
+        assert_eq!(recorded, declared);
+    }
+}
@@ -0,0 +1,360 @@
+//! The parameter space covered by the benchmarks.
+
+use crate::{error::HashBenchError, registry::HasherRegistry};
+use rand::{seq::SliceRandom, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{env, fmt, fs, path::PathBuf, str::FromStr, thread};
+
+/// Environment variable selecting the data patterns of [`BenchMatrix::from_env`], as a comma
+/// separated list of patterns in the form accepted by [`DataPattern::from_str`].
+pub const PATTERNS_VAR: &str = "HASH_BENCH_PATTERNS";
+/// Environment variable selecting the thread counts of [`BenchMatrix::from_env`], as a comma
+/// separated list.
+pub const THREADS_VAR: &str = "HASH_BENCH_THREADS";
+/// Environment variable overriding the seed of [`BenchMatrix::from_env`].
+pub const SEED_VAR: &str = "HASH_BENCH_SEED";
+/// Environment variable which adds the inputs of [`BenchMatrix::with_large_inputs`] to
+/// [`BenchMatrix::from_env`] when set to 1.
+pub const LARGE_INPUTS_VAR: &str = "HASH_BENCH_LARGE_INPUTS";
+
+/// Seed of the generated data patterns, unless configured otherwise.
+pub const DEFAULT_SEED: u64 = 0x6861_7368_5f62_656e;
+
+/// The seed of [`SEED_VAR`], or [`DEFAULT_SEED`] if it isn't set.
+pub fn seed_from_env() -> Result<u64, HashBenchError> {
+    match env::var(SEED_VAR) {
+        Ok(seed) => seed.parse().map_err(|_| HashBenchError::InvalidSeed(seed)),
+        Err(_) => Ok(DEFAULT_SEED),
+    }
+}
+
+/// Words from which [`DataPattern::Text`] is made up.
+const WORDS: &[&str] = &[
+    "the", "of", "and", "to", "in", "hash", "is", "that", "for", "it", "as", "with", "was", "on",
+    "data", "be", "at", "by", "this", "had", "from", "or", "but", "block", "are", "an", "they",
+    "which", "you", "one", "we", "all", "were", "her", "would", "there", "their", "will", "when",
+    "who", "him", "been", "has", "more", "if", "no", "out", "so", "said", "what", "up", "its",
+    "about", "than", "into", "them", "can", "only", "other", "new", "some", "could", "time",
+];
+
+/// The kind of data a benchmark input is filled with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataPattern {
+    /// Random bytes from ChaCha8 seeded with the seed of the matrix, which are the same on every
+    /// machine.
+    Random,
+    /// All bytes 0.
+    Zeros,
+    /// The same byte, repeated.
+    Repeating(u8),
+    /// Random words of ASCII text, split in lines and sentences.
+    Text,
+    /// Newline delimited JSON records, differing only in a few fields, so highly compressible.
+    Json,
+    /// The contents of a file, repeated as often as needed.
+    Corpus(PathBuf),
+}
+
+impl DataPattern {
+    /// Generate an input of `len` bytes following this pattern. Random patterns are generated
+    /// from `seed`.
+    pub fn generate(&self, len: usize, seed: u64) -> Result<Vec<u8>, HashBenchError> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut bytes = Vec::with_capacity(len);
+        match self {
+            DataPattern::Random => {
+                bytes.resize(len, 0);
+                rng.fill_bytes(&mut bytes);
+            }
+            DataPattern::Zeros => bytes.resize(len, 0),
+            DataPattern::Repeating(b) => bytes.resize(len, *b),
+            DataPattern::Text => {
+                while bytes.len() < len {
+                    let words = rng.gen_range(4..16);
+                    for i in 0..words {
+                        let word = WORDS.choose(&mut rng).unwrap().as_bytes();
+                        if i == 0 {
+                            bytes.push(word[0].to_ascii_uppercase());
+                            bytes.extend_from_slice(&word[1..]);
+                        } else {
+                            bytes.push(b' ');
+                            bytes.extend_from_slice(word);
+                        }
+                    }
+                    bytes.extend_from_slice(if rng.gen_ratio(1, 4) { b".\n" } else { b". " });
+                }
+            }
+            DataPattern::Json => {
+                let mut id = 0u64;
+                while bytes.len() < len {
+                    let record = format!(
+                        "{{\"id\":{id},\"name\":\"user-{id}\",\"active\":{},\"roles\":[\"reader\",\"writer\"],\"quota\":{}}}\n",
+                        !id.is_multiple_of(3),
+                        1024 * (id % 8),
+                    );
+                    bytes.extend_from_slice(record.as_bytes());
+                    id += 1;
+                }
+            }
+            DataPattern::Corpus(path) => {
+                let corpus = fs::read(path).map_err(|e| HashBenchError::Corpus {
+                    path: path.clone(),
+                    reason: e.to_string(),
+                })?;
+                if corpus.is_empty() && len > 0 {
+                    return Err(HashBenchError::Corpus {
+                        path: path.clone(),
+                        reason: "file is empty".into(),
+                    });
+                }
+                bytes.extend(corpus.iter().cycle().take(len));
+            }
+        }
+        bytes.truncate(len);
+        Ok(bytes)
+    }
+}
+
+impl fmt::Display for DataPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataPattern::Random => f.write_str("random"),
+            DataPattern::Zeros => f.write_str("zeros"),
+            DataPattern::Repeating(b) => write!(f, "repeat-{b:02x}"),
+            DataPattern::Text => f.write_str("text"),
+            DataPattern::Json => f.write_str("json"),
+            DataPattern::Corpus(path) => match path.file_name() {
+                Some(name) => write!(f, "corpus-{}", name.to_string_lossy()),
+                None => write!(f, "corpus-{}", path.display()),
+            },
+        }
+    }
+}
+
+impl FromStr for DataPattern {
+    type Err = HashBenchError;
+
+    /// Parse the name of a pattern as displayed, except for corpus files which are given as
+    /// `corpus:<path>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "random" => DataPattern::Random,
+            "zeros" => DataPattern::Zeros,
+            "text" => DataPattern::Text,
+            "json" => DataPattern::Json,
+            _ => {
+                if let Some(path) = s.strip_prefix("corpus:") {
+                    DataPattern::Corpus(path.into())
+                } else if let Some(byte) = s
+                    .strip_prefix("repeat-")
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+                {
+                    DataPattern::Repeating(byte)
+                } else {
+                    return Err(HashBenchError::UnknownPattern(s.into()));
+                }
+            }
+        })
+    }
+}
+
+/// Every combination of algorithm, chunk size, input size and data pattern to benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchMatrix {
+    /// Names of the algorithms to benchmark, as known by a [`HasherRegistry`].
+    pub algorithms: Vec<String>,
+    /// Sizes of the pieces in which the input is fed to the hasher.
+    pub chunk_sizes: Vec<usize>,
+    /// Total size of the input which is hashed in pieces of every chunk size.
+    pub chunked_input_size: usize,
+    /// Total sizes of the inputs to hash, each in a single piece.
+    pub input_sizes: Vec<usize>,
+    /// Data patterns the inputs are filled with.
+    pub patterns: Vec<DataPattern>,
+    /// Seed of the random data patterns.
+    pub seed: u64,
+    /// Numbers of threads used by the multi-threaded hashers.
+    pub thread_counts: Vec<usize>,
+}
+
+impl BenchMatrix {
+    /// The default matrix: every algorithm in `registry`, hashing 1 MiB of random data in chunks
+    /// of 16 bytes up to 512 KiB, and random inputs of 64 bytes up to 64 MiB in a single piece.
+    pub fn new(registry: &HasherRegistry) -> Self {
+        Self {
+            algorithms: registry.names().map(String::from).collect(),
+            chunk_sizes: (0..16).map(|i| 16 << i).collect(),
+            chunked_input_size: 1 << 20,
+            input_sizes: (0..11).map(|i| 64 << (2 * i)).collect(),
+            patterns: vec![DataPattern::Random],
+            seed: DEFAULT_SEED,
+            thread_counts: default_thread_counts(),
+        }
+    }
+
+    /// The default matrix, with the data patterns of [`PATTERNS_VAR`], the thread counts of
+    /// [`THREADS_VAR`], the seed of [`SEED_VAR`] and the large inputs if [`LARGE_INPUTS_VAR`] is
+    /// set.
+    pub fn from_env(registry: &HasherRegistry) -> Result<Self, HashBenchError> {
+        let mut matrix = Self::new(registry);
+        if let Ok(patterns) = env::var(PATTERNS_VAR) {
+            matrix.patterns = patterns
+                .split(',')
+                .map(|p| p.trim().parse())
+                .collect::<Result<_, _>>()?;
+        }
+        if let Ok(threads) = env::var(THREADS_VAR) {
+            matrix.thread_counts = threads
+                .split(',')
+                .map(|t| t.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| HashBenchError::InvalidThreadCount)?;
+        }
+        matrix.seed = seed_from_env()?;
+        if env::var(LARGE_INPUTS_VAR).is_ok_and(|v| v == "1") {
+            matrix = matrix.with_large_inputs();
+        }
+        Ok(matrix)
+    }
+
+    /// Add inputs of 256 MiB, 1 GiB and, on 64 bit targets, 4 GiB to the matrix. These need as
+    /// much memory, and take a long time to benchmark.
+    pub fn with_large_inputs(mut self) -> Self {
+        self.input_sizes.extend([256 << 20, 1 << 30]);
+        #[cfg(target_pointer_width = "64")]
+        self.input_sizes.push(4 << 30);
+        self
+    }
+
+    /// The size of the largest input of the matrix.
+    pub fn max_input_size(&self) -> usize {
+        self.input_sizes
+            .iter()
+            .copied()
+            .fold(self.chunked_input_size, usize::max)
+    }
+
+    /// Check that every dimension of the matrix has at least one value, every algorithm is known
+    /// by `registry`, and no chunk size or thread count is 0.
+    pub fn validate(&self, registry: &HasherRegistry) -> Result<(), HashBenchError> {
+        if self.algorithms.is_empty() {
+            return Err(HashBenchError::EmptyDimension("algorithms"));
+        }
+        if self.chunk_sizes.is_empty() {
+            return Err(HashBenchError::EmptyDimension("chunk sizes"));
+        }
+        if self.input_sizes.is_empty() {
+            return Err(HashBenchError::EmptyDimension("input sizes"));
+        }
+        if self.patterns.is_empty() {
+            return Err(HashBenchError::EmptyDimension("data patterns"));
+        }
+        if self.thread_counts.is_empty() {
+            return Err(HashBenchError::EmptyDimension("thread counts"));
+        }
+        if let Some(name) = self.algorithms.iter().find(|a| !registry.contains(a)) {
+            return Err(HashBenchError::UnknownAlgorithm(name.clone()));
+        }
+        if self.chunk_sizes.contains(&0) {
+            return Err(HashBenchError::InvalidChunkSize);
+        }
+        if self.thread_counts.contains(&0) {
+            return Err(HashBenchError::InvalidThreadCount);
+        }
+        Ok(())
+    }
+}
+
+/// Powers of two below the available parallelism, and the available parallelism itself.
+fn default_thread_counts() -> Vec<usize> {
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut counts: Vec<_> = (0..).map(|i| 1 << i).take_while(|&n| n < cores).collect();
+    counts.push(cores);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchMatrix, DataPattern, DEFAULT_SEED};
+    use crate::{error::HashBenchError, registry::HasherRegistry};
+
+    #[test]
+    fn validate() {
+        let registry = HasherRegistry::builtin();
+        let matrix = BenchMatrix::new(&registry);
+        assert_eq!(matrix.validate(&registry), Ok(()));
+        assert_eq!(matrix.max_input_size(), 64 << 20);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(matrix.clone().with_large_inputs().max_input_size(), 4 << 30);
+        #[cfg(not(target_pointer_width = "64"))]
+        assert_eq!(matrix.clone().with_large_inputs().max_input_size(), 1 << 30);
+
+        let mut invalid = matrix.clone();
+        invalid.chunk_sizes.push(0);
+        assert_eq!(
+            invalid.validate(&registry),
+            Err(HashBenchError::InvalidChunkSize)
+        );
+
+        let mut invalid = matrix.clone();
+        invalid.thread_counts.push(0);
+        assert_eq!(
+            invalid.validate(&registry),
+            Err(HashBenchError::InvalidThreadCount)
+        );
+
+        let mut invalid = matrix.clone();
+        invalid.algorithms.push("sha1".into());
+        assert_eq!(
+            invalid.validate(&registry),
+            Err(HashBenchError::UnknownAlgorithm("sha1".into()))
+        );
+
+        let mut invalid = matrix;
+        invalid.input_sizes.clear();
+        assert_eq!(
+            invalid.validate(&registry),
+            Err(HashBenchError::EmptyDimension("input sizes"))
+        );
+    }
+
+    #[test]
+    fn data_patterns() {
+        let patterns = [
+            DataPattern::Random,
+            DataPattern::Zeros,
+            DataPattern::Repeating(0xa5),
+            DataPattern::Text,
+            DataPattern::Json,
+            DataPattern::Corpus(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml").into()),
+        ];
+        for pattern in patterns {
+            let data = pattern.generate(1000, DEFAULT_SEED).unwrap();
+            assert_eq!(data.len(), 1000, "{pattern}");
+            assert_eq!(pattern.generate(1000, DEFAULT_SEED).unwrap(), data);
+            if pattern == DataPattern::Text || pattern == DataPattern::Json {
+                assert!(data.is_ascii(), "{pattern}");
+            }
+            if !matches!(pattern, DataPattern::Corpus(_)) {
+                assert_eq!(pattern.to_string().parse(), Ok(pattern));
+            }
+        }
+
+        assert_ne!(
+            DataPattern::Random.generate(64, 1).unwrap(),
+            DataPattern::Random.generate(64, 2).unwrap()
+        );
+        assert_eq!(
+            "corpus:Cargo.toml".parse(),
+            Ok(DataPattern::Corpus("Cargo.toml".into()))
+        );
+        assert_eq!(
+            "sparse".parse::<DataPattern>(),
+            Err(HashBenchError::UnknownPattern("sparse".into()))
+        );
+        assert!(matches!(
+            DataPattern::Corpus("/nonexistent".into()).generate(1, DEFAULT_SEED),
+            Err(HashBenchError::Corpus { .. })
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+//! Avalanche and bit independence criteria.
+
+use super::{QualityTest, Subject, TestResult, Z_LIMIT};
+use rand::Rng;
+
+/// Length in bytes of the keys whose bits are flipped.
+const KEY_LEN: usize = 16;
+
+/// The digest differences caused by flipping single input bits.
+pub(super) struct Diffs {
+    trials: usize,
+    /// `diffs[trial * KEY_LEN * 8 + bit]` is the digest difference caused by flipping `bit` in
+    /// the key of `trial`.
+    diffs: Vec<u64>,
+}
+
+/// Flip every bit of `trials` random keys, and record the resulting digest differences.
+pub(super) fn flip_bits(subject: &Subject, trials: usize, rng: &mut impl Rng) -> Diffs {
+    let mut diffs = Vec::with_capacity(trials * KEY_LEN * 8);
+    let mut key = [0; KEY_LEN];
+    for _ in 0..trials {
+        rng.fill_bytes(&mut key);
+        let original = subject.hash(&key);
+        for bit in 0..KEY_LEN * 8 {
+            key[bit / 8] ^= 1 << (bit % 8);
+            diffs.push(original ^ subject.hash(&key));
+            key[bit / 8] ^= 1 << (bit % 8);
+        }
+    }
+    Diffs { trials, diffs }
+}
+
+impl Diffs {
+    /// The differences caused by flipping `bit`, one per trial.
+    fn of_input_bit(&self, bit: usize) -> impl Iterator<Item = u64> + '_ {
+        self.diffs[bit..].iter().step_by(KEY_LEN * 8).copied()
+    }
+}
+
+/// For every input bit, the number of trials in which each output bit flipped.
+fn flip_counts(subject: &Subject, diffs: &Diffs, input_bit: usize) -> Vec<u64> {
+    let mut counts = vec![0; subject.bits as usize];
+    for diff in diffs.of_input_bit(input_bit) {
+        for (j, count) in counts.iter_mut().enumerate() {
+            *count += diff >> j & 1;
+        }
+    }
+    counts
+}
+
+/// The strict avalanche criterion: every output bit should flip with a probability of one half
+/// when any input bit is flipped.
+pub(super) fn avalanche(subject: &Subject, diffs: &Diffs) -> TestResult {
+    let trials = diffs.trials as f64;
+    let worst = (0..KEY_LEN * 8)
+        .flat_map(|i| flip_counts(subject, diffs, i))
+        .map(|count| (2.0 * count as f64 / trials - 1.0).abs())
+        .fold(0.0, f64::max);
+    TestResult {
+        test: QualityTest::Avalanche,
+        score: worst,
+        // The bias of an ideal hash has a standard deviation of 1 / sqrt(trials).
+        limit: Z_LIMIT / trials.sqrt(),
+    }
+}
+
+/// The bit independence criterion: when an input bit is flipped, the changes of any two output
+/// bits should be uncorrelated.
+pub(super) fn bit_independence(subject: &Subject, diffs: &Diffs) -> TestResult {
+    let bits = subject.bits as usize;
+    let trials = diffs.trials as f64;
+    let mut worst: f64 = 0.0;
+    let mut both = vec![0u64; bits * bits];
+    for i in 0..KEY_LEN * 8 {
+        both.iter_mut().for_each(|c| *c = 0);
+        for diff in diffs.of_input_bit(i) {
+            let mut rest = diff;
+            while rest != 0 {
+                let j = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                let mut others = rest;
+                while others != 0 {
+                    both[j * bits + others.trailing_zeros() as usize] += 1;
+                    others &= others - 1;
+                }
+            }
+        }
+
+        let single = flip_counts(subject, diffs, i);
+        for j in 0..bits {
+            for k in j + 1..bits {
+                let (nj, nk) = (single[j] as f64, single[k] as f64);
+                let denominator = (nj * (trials - nj) * nk * (trials - nk)).sqrt();
+                // A bit which always or never changes is as dependent as it gets.
+                let phi = if denominator == 0.0 {
+                    1.0
+                } else {
+                    (trials * both[j * bits + k] as f64 - nj * nk) / denominator
+                };
+                worst = worst.max(phi.abs());
+            }
+        }
+    }
+    TestResult {
+        test: QualityTest::BitIndependence,
+        score: worst,
+        // The correlation of an ideal hash has a standard deviation of 1 / sqrt(trials).
+        limit: Z_LIMIT / trials.sqrt(),
+    }
+}
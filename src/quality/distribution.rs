@@ -0,0 +1,40 @@
+//! Bucket distribution of sequential keys.
+
+use super::{QualityTest, Subject, TestResult, Z_LIMIT};
+
+/// Expected number of keys per bucket, high enough for the chi-square test to be accurate.
+const KEYS_PER_BUCKET: usize = 16;
+
+/// Hash the little endian integers `0..keys`, and compute the chi-square statistic of the
+/// buckets selected by every window of digest bits, as a hash table or sharding scheme would.
+pub(super) fn distribution(subject: &Subject, keys: usize) -> TestResult {
+    let hashes: Vec<_> = (0..keys as u64)
+        .map(|i| subject.hash(&i.to_le_bytes()))
+        .collect();
+
+    let window = (keys / KEYS_PER_BUCKET).max(2).ilog2().min(subject.bits);
+    let buckets = 1usize << window;
+    let expected = keys as f64 / buckets as f64;
+    let df = (buckets - 1) as f64;
+
+    let mut counts = vec![0u64; buckets];
+    let mut worst = f64::NEG_INFINITY;
+    for offset in 0..=subject.bits - window {
+        counts.iter_mut().for_each(|c| *c = 0);
+        for h in &hashes {
+            counts[(h >> offset) as usize & (buckets - 1)] += 1;
+        }
+        let chi_square: f64 = counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum();
+        // Normal approximation of the chi-square distribution. Only overly uneven distributions
+        // are a problem, an overly even one means sequential keys are spread perfectly.
+        worst = worst.max((chi_square - df) / (2.0 * df).sqrt());
+    }
+    TestResult {
+        test: QualityTest::Distribution,
+        score: worst,
+        limit: Z_LIMIT,
+    }
+}
@@ -0,0 +1,73 @@
+//! Collision tests on structured key sets.
+
+use super::{collision_result, collisions, QualityTest, Subject, TestResult};
+use rand::Rng;
+
+/// Length in bytes of the sparse keys.
+const SPARSE_KEY_LEN: usize = 32;
+/// Length in bytes of the repeated part of a cyclic key.
+const CYCLE_LEN: usize = 8;
+/// Number of times the cycle is repeated in a cyclic key.
+const CYCLE_REPEATS: usize = 8;
+/// Length in bytes of the keys to which differences are applied.
+const DIFFERENTIAL_KEY_LEN: usize = 8;
+
+/// Hash every key of [`SPARSE_KEY_LEN`] bytes with at most 2 bits set.
+pub(super) fn sparse_keys(subject: &Subject) -> TestResult {
+    let bits = SPARSE_KEY_LEN * 8;
+    let mut hashes = vec![subject.hash(&[0; SPARSE_KEY_LEN])];
+    let mut key = [0; SPARSE_KEY_LEN];
+    for i in 0..bits {
+        key[i / 8] ^= 1 << (i % 8);
+        hashes.push(subject.hash(&key));
+        for j in i + 1..bits {
+            key[j / 8] ^= 1 << (j % 8);
+            hashes.push(subject.hash(&key));
+            key[j / 8] ^= 1 << (j % 8);
+        }
+        key[i / 8] ^= 1 << (i % 8);
+    }
+    let expected = subject.expected_collisions(hashes.len());
+    collision_result(QualityTest::SparseKeys, collisions(hashes), expected)
+}
+
+/// Hash `keys` keys consisting of a random cycle of [`CYCLE_LEN`] bytes, repeated
+/// [`CYCLE_REPEATS`] times.
+pub(super) fn cyclic_keys(subject: &Subject, keys: usize, rng: &mut impl Rng) -> TestResult {
+    let mut cycle = [0; CYCLE_LEN];
+    let hashes: Vec<_> = (0..keys)
+        .map(|_| {
+            rng.fill_bytes(&mut cycle);
+            subject.hash(&cycle.repeat(CYCLE_REPEATS))
+        })
+        .collect();
+    let expected = subject.expected_collisions(keys);
+    collision_result(QualityTest::CyclicKeys, collisions(hashes), expected)
+}
+
+/// Flip every combination of 1 and 2 bits in `keys` random keys, and count how often this does
+/// not change the digest.
+pub(super) fn differential(subject: &Subject, keys: usize, rng: &mut impl Rng) -> TestResult {
+    let bits = DIFFERENTIAL_KEY_LEN * 8;
+    let mut key = [0; DIFFERENTIAL_KEY_LEN];
+    let mut observed = 0;
+    let mut trials = 0;
+    for _ in 0..keys {
+        rng.fill_bytes(&mut key);
+        let original = subject.hash(&key);
+        for i in 0..bits {
+            key[i / 8] ^= 1 << (i % 8);
+            observed += (subject.hash(&key) == original) as usize;
+            for j in i + 1..bits {
+                key[j / 8] ^= 1 << (j % 8);
+                observed += (subject.hash(&key) == original) as usize;
+                key[j / 8] ^= 1 << (j % 8);
+                trials += 1;
+            }
+            key[i / 8] ^= 1 << (i % 8);
+            trials += 1;
+        }
+    }
+    let expected = trials as f64 / 2f64.powi(subject.bits as i32);
+    collision_result(QualityTest::Differential, observed, expected)
+}
@@ -0,0 +1,268 @@
+//! Statistical quality tests in the style of SMHasher, to judge whether a hasher is fit for hash
+//! table or sharding use, independent of its speed.
+//!
+//! Digests longer than 64 bits are analysed on their first 64 bits, which is what a consumer
+//! truncating the digest to a machine word sees.
+
+mod avalanche;
+mod distribution;
+mod keysets;
+
+use crate::{error::HashBenchError, matrix::DEFAULT_SEED, registry::HasherRegistry};
+use rand::{rngs::StdRng, SeedableRng};
+use std::fmt;
+
+/// Deviation, in standard deviations of an ideal hash, above which a normally distributed
+/// statistic fails a test.
+const Z_LIMIT: f64 = 6.0;
+
+/// Probability of an ideal hash exceeding the collision limit of a test.
+const COLLISION_SIGNIFICANCE: f64 = 1e-9;
+
+/// Parameters of the quality tests.
+#[derive(Debug, Clone)]
+pub struct QualityConfig {
+    /// Seed of the random keys, so runs can be reproduced.
+    pub seed: u64,
+    /// Number of random keys whose bits are flipped by the avalanche and bit independence tests.
+    pub trials: usize,
+    /// Number of keys hashed by the cyclic key and distribution tests.
+    pub keys: usize,
+    /// Number of random keys to which every 1 and 2 bit difference is applied by the
+    /// differential test.
+    pub differential_keys: usize,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_SEED,
+            trials: 2000,
+            keys: 100_000,
+            differential_keys: 100,
+        }
+    }
+}
+
+/// A single statistical test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityTest {
+    /// Worst bias of an output bit when flipping an input bit, from 0 (flips half of the time)
+    /// to 1 (always or never flips).
+    Avalanche,
+    /// Worst absolute correlation between the changes of two output bits when flipping an input
+    /// bit.
+    BitIndependence,
+    /// Collisions among all keys of 32 bytes with at most 2 bits set.
+    SparseKeys,
+    /// Collisions among keys made of a random 8 byte cycle, repeated 8 times.
+    CyclicKeys,
+    /// Collisions between random keys and the same keys with 1 or 2 bits flipped.
+    Differential,
+    /// Worst chi-square deviation, in standard deviations, of the buckets selected by any window
+    /// of bits of the digests of sequential integer keys.
+    Distribution,
+}
+
+impl fmt::Display for QualityTest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            QualityTest::Avalanche => "avalanche",
+            QualityTest::BitIndependence => "bit independence",
+            QualityTest::SparseKeys => "sparse keys",
+            QualityTest::CyclicKeys => "cyclic keys",
+            QualityTest::Differential => "differential",
+            QualityTest::Distribution => "distribution",
+        })
+    }
+}
+
+/// The outcome of a [`QualityTest`]. Lower scores are better.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    /// The test which was run.
+    pub test: QualityTest,
+    /// The measured statistic, see [`QualityTest`] for its meaning.
+    pub score: f64,
+    /// The highest score an ideal hash is expected to reach.
+    pub limit: f64,
+}
+
+impl TestResult {
+    /// Whether the score is within the limit.
+    pub fn passed(&self) -> bool {
+        self.score <= self.limit
+    }
+}
+
+/// The results of all quality tests for a single algorithm.
+#[derive(Debug, Clone)]
+pub struct Scorecard {
+    /// Name of the algorithm in the [`HasherRegistry`].
+    pub algorithm: String,
+    /// Number of digest bits which were analysed.
+    pub bits: u32,
+    /// The result of every test, in the order of [`QualityTest`].
+    pub results: Vec<TestResult>,
+}
+
+impl Scorecard {
+    /// Whether every test passed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(TestResult::passed)
+    }
+
+    /// The result of `test`.
+    pub fn result(&self, test: QualityTest) -> Option<&TestResult> {
+        self.results.iter().find(|r| r.test == test)
+    }
+}
+
+impl fmt::Display for Scorecard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({} bits analysed)", self.algorithm, self.bits)?;
+        for r in &self.results {
+            writeln!(
+                f,
+                "  {:<18} {:>12.4} (limit {:.4}) {}",
+                r.test.to_string(),
+                r.score,
+                r.limit,
+                if r.passed() { "pass" } else { "FAIL" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run every quality test against the algorithm registered as `name`.
+pub fn scorecard(
+    registry: &HasherRegistry,
+    name: &str,
+    config: &QualityConfig,
+) -> Result<Scorecard, HashBenchError> {
+    let output_len = registry
+        .output_len(name)
+        .ok_or_else(|| HashBenchError::UnknownAlgorithm(name.into()))?;
+    let subject = Subject {
+        registry,
+        name,
+        bits: (output_len.min(8) * 8) as u32,
+    };
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    let diffs = avalanche::flip_bits(&subject, config.trials, &mut rng);
+    let results = vec![
+        avalanche::avalanche(&subject, &diffs),
+        avalanche::bit_independence(&subject, &diffs),
+        keysets::sparse_keys(&subject),
+        keysets::cyclic_keys(&subject, config.keys, &mut rng),
+        keysets::differential(&subject, config.differential_keys, &mut rng),
+        distribution::distribution(&subject, config.keys),
+    ];
+
+    Ok(Scorecard {
+        algorithm: name.into(),
+        bits: subject.bits,
+        results,
+    })
+}
+
+/// The hasher under test, reduced to its first `bits` bits.
+struct Subject<'a> {
+    registry: &'a HasherRegistry,
+    name: &'a str,
+    bits: u32,
+}
+
+impl Subject<'_> {
+    /// The analysed bits of the digest of `key`, in the low bits of the result.
+    fn hash(&self, key: &[u8]) -> u64 {
+        let mut hasher = self.registry.get(self.name).expect("registered algorithm");
+        hasher.update(key);
+        hasher.finalize()[..self.bits as usize / 8]
+            .iter()
+            .fold(0, |acc, &b| acc << 8 | b as u64)
+    }
+
+    /// The number of pairs among `n` values expected to collide for an ideal hash.
+    fn expected_collisions(&self, n: usize) -> f64 {
+        let n = n as f64;
+        n * (n - 1.0) / 2.0 / 2f64.powi(self.bits as i32)
+    }
+}
+
+/// The number of values which are equal to an earlier value in `hashes`.
+fn collisions(mut hashes: Vec<u64>) -> usize {
+    hashes.sort_unstable();
+    hashes.windows(2).filter(|w| w[0] == w[1]).count()
+}
+
+/// The smallest count which a Poisson distributed variable with mean `expected` exceeds with a
+/// probability below [`COLLISION_SIGNIFICANCE`].
+pub(crate) fn collision_limit(expected: f64) -> f64 {
+    // The pmf underflows for large means, where the normal approximation is accurate anyway.
+    if expected > 100.0 {
+        return (expected + Z_LIMIT * expected.sqrt()).ceil();
+    }
+    let mut pmf = (-expected).exp();
+    let mut cdf = pmf;
+    let mut k = 0.0;
+    while 1.0 - cdf >= COLLISION_SIGNIFICANCE {
+        k += 1.0;
+        pmf *= expected / k;
+        cdf += pmf;
+    }
+    k
+}
+
+/// Compare an observed number of collisions against the number expected of an ideal hash.
+fn collision_result(test: QualityTest, observed: usize, expected: f64) -> TestResult {
+    TestResult {
+        test,
+        score: observed as f64,
+        limit: collision_limit(expected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collision_limit, scorecard, QualityConfig, QualityTest};
+    use crate::registry::HasherRegistry;
+
+    fn config() -> QualityConfig {
+        QualityConfig {
+            trials: 200,
+            keys: 4096,
+            differential_keys: 2,
+            ..QualityConfig::default()
+        }
+    }
+
+    #[test]
+    fn good_hash_passes() {
+        let card = scorecard(&HasherRegistry::builtin(), "xxh3-64", &config()).unwrap();
+        assert_eq!(card.bits, 64);
+        assert!(card.passed(), "{card}");
+    }
+
+    #[test]
+    fn weak_checksum_fails() {
+        let card = scorecard(&HasherRegistry::builtin(), "adler32", &config()).unwrap();
+        assert_eq!(card.bits, 32);
+        assert!(
+            !card.result(QualityTest::Avalanche).unwrap().passed(),
+            "{card}"
+        );
+        assert!(
+            !card.result(QualityTest::Distribution).unwrap().passed(),
+            "{card}"
+        );
+    }
+
+    #[test]
+    fn poisson_limit() {
+        assert_eq!(collision_limit(0.0), 0.0);
+        assert_eq!(collision_limit(0.126), 6.0);
+    }
+}
@@ -0,0 +1,295 @@
+//! Runtime selection of hashers by name.
+
+use crate::error::HashBenchError;
+use crate::hashers::{
+    Adler32Hasher, Blake2Hasher32, Blake2Hasher64, Blake2bMac32, Blake2bMac64, Blake2bpHasher64,
+    Blake2spHasher32, Blake3DeriveKeyHasher32, Blake3Hasher32, Blake3Hasher64, Blake3KeyedHasher32,
+    Blake3RayonHasher32, Crc32Hasher, Crc32TableHasher, Crc32cHasher, Crc32cTableHasher,
+    Crc64EcmaHasher, Crc64NvmeHasher, Crc64NvmeTableHasher, Fletcher32Hasher, Hasher,
+    HmacSha256Hasher, HmacSha3_256Hasher, HmacSha512Hasher, KangarooTwelveHasher32, Md5hasher,
+    Murmur3Hasher128, Murmur3Hasher32, Sha224Hasher, Sha256Hasher, Sha384Hasher, Sha3_256Hasher,
+    Sha3_512Hasher, Sha512Hasher, Sha512_256Hasher, Shake128Hasher32, Shake256Hasher64, WyHasher,
+    Xxh32Hasher, Xxh3Hasher128, Xxh3Hasher64, Xxh64Hasher,
+};
+
+/// Object safe counterpart of [`Hasher`], producing the digest as a byte vector.
+///
+/// This is implemented for every [`Hasher`] whose output can be viewed as a byte slice.
+pub trait DynHasher: Send {
+    /// Feed `data` into the hasher.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consume the hasher and return the digest of all data fed to it so far.
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+
+    /// Reset the hasher to its initial state, discarding all data fed to it so far.
+    fn reset(&mut self);
+
+    /// Consume the hasher, feeding it `chunks` in pieces of `chunk_size` bytes, and return the
+    /// resulting digest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    fn hash(mut self: Box<Self>, chunks: &[u8], chunk_size: usize) -> Vec<u8> {
+        for c in chunks.chunks(chunk_size) {
+            self.update(c);
+        }
+        self.finalize()
+    }
+
+    /// Like [`hash`](DynHasher::hash), but returns an error instead of panicking if `chunk_size`
+    /// is 0.
+    fn try_hash(
+        self: Box<Self>,
+        chunks: &[u8],
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, HashBenchError> {
+        if chunk_size == 0 {
+            return Err(HashBenchError::InvalidChunkSize);
+        }
+        Ok(self.hash(chunks, chunk_size))
+    }
+
+    /// Consume the hasher and return the first `len` bytes of the digest, or an error if the
+    /// digest is shorter than that.
+    fn finalize_truncated(self: Box<Self>, len: usize) -> Result<Vec<u8>, HashBenchError> {
+        let mut digest = self.finalize();
+        if len > digest.len() {
+            return Err(HashBenchError::UnsupportedOutputLength {
+                requested: len,
+                supported: digest.len(),
+            });
+        }
+        digest.truncate(len);
+        Ok(digest)
+    }
+}
+
+impl<H> DynHasher for H
+where
+    H: Hasher + Send,
+    H::Output: AsRef<[u8]>,
+{
+    fn update(&mut self, data: &[u8]) {
+        Hasher::update(self, data)
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        Hasher::finalize(*self).as_ref().to_vec()
+    }
+
+    fn reset(&mut self) {
+        Hasher::reset(self)
+    }
+}
+
+/// Key used by the keyed hashers in [`HasherRegistry::builtin`].
+pub const BENCH_KEY: [u8; 32] = *b"hash_bench benchmarking key 0001";
+/// Salt used by the BLAKE2 MACs in [`HasherRegistry::builtin`].
+pub const BENCH_SALT: [u8; 16] = *b"hash_bench salt!";
+/// Personalization string used by the BLAKE2 MACs in [`HasherRegistry::builtin`].
+pub const BENCH_PERSONA: [u8; 16] = *b"hash_bench perso";
+/// Context string used by BLAKE3 key derivation in [`HasherRegistry::builtin`].
+pub const BENCH_CONTEXT: &str = "hash_bench 2022-06-01 benchmark key derivation";
+
+type Constructor = Box<dyn Fn() -> Box<dyn DynHasher> + Send + Sync>;
+
+/// Hashes a single message, replacing the contents of the buffer with the digest.
+pub type Digester = dyn Fn(&[u8], &mut Vec<u8>) + Send + Sync;
+
+struct Entry {
+    name: &'static str,
+    output_len: usize,
+    new: Constructor,
+    digest: Box<Digester>,
+}
+
+/// A collection of hashers, addressable by name.
+///
+/// Algorithms are kept in the order in which they are registered.
+#[derive(Default)]
+pub struct HasherRegistry {
+    entries: Vec<Entry>,
+}
+
+impl HasherRegistry {
+    /// Create a registry without any algorithms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry containing all single threaded hashers provided by this crate. Keyed
+    /// hashers use [`BENCH_KEY`], [`BENCH_SALT`], [`BENCH_PERSONA`] and [`BENCH_CONTEXT`].
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register("md5", Md5hasher::new);
+        registry.register("blake2b-256", Blake2Hasher32::new);
+        registry.register("blake2b-512", Blake2Hasher64::new);
+        registry.register("blake3-256", Blake3Hasher32::new);
+        registry.register("blake3-512", Blake3Hasher64::new);
+        registry.register("blake2bp-512", Blake2bpHasher64::new);
+        registry.register("blake2sp-256", Blake2spHasher32::new);
+        registry.register("blake3-256-keyed", || Blake3KeyedHasher32::new(&BENCH_KEY));
+        registry.register("blake3-256-derive-key", || {
+            Blake3DeriveKeyHasher32::new(BENCH_CONTEXT)
+        });
+        registry.register("blake2b-256-mac", || {
+            Blake2bMac32::new(&BENCH_KEY, &BENCH_SALT, &BENCH_PERSONA).expect("valid parameters")
+        });
+        registry.register("blake2b-512-mac", || {
+            Blake2bMac64::new(&BENCH_KEY, &BENCH_SALT, &BENCH_PERSONA).expect("valid parameters")
+        });
+        registry.register("crc32", Crc32Hasher::new);
+        registry.register("crc32-table", Crc32TableHasher::new);
+        registry.register("crc32c", Crc32cHasher::new);
+        registry.register("crc32c-table", Crc32cTableHasher::new);
+        registry.register("crc64-ecma", Crc64EcmaHasher::new);
+        registry.register("crc64-nvme", Crc64NvmeHasher::new);
+        registry.register("crc64-nvme-table", Crc64NvmeTableHasher::new);
+        registry.register("adler32", Adler32Hasher::new);
+        registry.register("fletcher32", Fletcher32Hasher::new);
+        registry.register("sha224", Sha224Hasher::new);
+        registry.register("sha256", Sha256Hasher::new);
+        registry.register("sha384", Sha384Hasher::new);
+        registry.register("sha512", Sha512Hasher::new);
+        registry.register("sha512-256", Sha512_256Hasher::new);
+        registry.register("sha3-256", Sha3_256Hasher::new);
+        registry.register("sha3-512", Sha3_512Hasher::new);
+        registry.register("hmac-sha256", || HmacSha256Hasher::new(&BENCH_KEY));
+        registry.register("hmac-sha512", || HmacSha512Hasher::new(&BENCH_KEY));
+        registry.register("hmac-sha3-256", || HmacSha3_256Hasher::new(&BENCH_KEY));
+        registry.register("shake128-256", Shake128Hasher32::new);
+        registry.register("shake256-512", Shake256Hasher64::new);
+        registry.register("k12-256", KangarooTwelveHasher32::new);
+        registry.register("xxh32", Xxh32Hasher::new);
+        registry.register("xxh64", Xxh64Hasher::new);
+        registry.register("xxh3-64", Xxh3Hasher64::new);
+        registry.register("xxh3-128", Xxh3Hasher128::new);
+        registry.register("wyhash", WyHasher::new);
+        registry.register("murmur3-32", Murmur3Hasher32::new);
+        registry.register("murmur3-128", Murmur3Hasher128::new);
+        registry
+    }
+
+    /// Create a registry containing the builtin hashers, and the hashers which split a single
+    /// input over the threads of the current rayon thread pool. These are kept out of
+    /// [`builtin`](Self::builtin), as their results depend on the number of threads.
+    pub fn parallel() -> Self {
+        let mut registry = Self::builtin();
+        registry.register("blake3-256-rayon", Blake3RayonHasher32::new);
+        registry
+    }
+
+    /// Register a hasher under `name`, using `new` to construct fresh instances. An existing
+    /// algorithm with the same name is replaced.
+    pub fn register<H>(&mut self, name: &'static str, new: fn() -> H)
+    where
+        H: Hasher + Send + 'static,
+        H::Output: AsRef<[u8]>,
+    {
+        let entry = Entry {
+            name,
+            output_len: new().finalize().as_ref().len(),
+            new: Box::new(move || Box::new(new())),
+            digest: Box::new(move |data, out| {
+                let mut hasher = new();
+                hasher.update(data);
+                out.clear();
+                out.extend_from_slice(hasher.finalize().as_ref());
+            }),
+        };
+        match self.entries.iter_mut().find(|e| e.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// The names of all registered algorithms.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|e| e.name)
+    }
+
+    /// Construct a new hasher for the algorithm registered as `name`.
+    pub fn get(&self, name: &str) -> Option<Box<dyn DynHasher>> {
+        self.entry(name).map(|e| (e.new)())
+    }
+
+    /// A function hashing a single message with the algorithm registered as `name`. Unlike
+    /// [`get`](HasherRegistry::get), the hasher lives on the stack and the digest is written to a
+    /// reusable buffer, so hashing short messages is not dominated by allocations.
+    pub fn digester(&self, name: &str) -> Option<&Digester> {
+        self.entry(name).map(|e| &*e.digest)
+    }
+
+    /// Like [`get`](HasherRegistry::get), but returns an error naming the algorithm if it is not
+    /// registered.
+    pub fn try_get(&self, name: &str) -> Result<Box<dyn DynHasher>, HashBenchError> {
+        self.get(name)
+            .ok_or_else(|| HashBenchError::UnknownAlgorithm(name.into()))
+    }
+
+    /// Whether an algorithm is registered as `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    /// The length in bytes of the digest produced by the algorithm registered as `name`.
+    pub fn output_len(&self, name: &str) -> Option<usize> {
+        self.entry(name).map(|e| e.output_len)
+    }
+
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HasherRegistry;
+    use crate::error::HashBenchError;
+    use crate::hashers::{Blake2Hasher32, Hasher};
+
+    #[test]
+    fn builtin_algorithms() {
+        let registry = HasherRegistry::builtin();
+
+        for name in registry.names() {
+            let digest = registry.get(name).unwrap().hash(b"abc", 1);
+            assert_eq!(Some(digest.len()), registry.output_len(name), "{name}");
+            let mut out = vec![0xff; 3];
+            registry.digester(name).unwrap()(b"abc", &mut out);
+            assert_eq!(out, digest, "{name}");
+        }
+        assert!(registry.get("sha1").is_none());
+        assert_eq!(
+            registry.get("blake2b-256").unwrap().hash(b"abc", 2),
+            Blake2Hasher32::new().hash(b"abc", 3)
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let registry = HasherRegistry::builtin();
+
+        assert_eq!(
+            registry.try_get("sha1").err(),
+            Some(HashBenchError::UnknownAlgorithm("sha1".into()))
+        );
+        assert_eq!(
+            registry.try_get("md5").unwrap().try_hash(b"abc", 0),
+            Err(HashBenchError::InvalidChunkSize)
+        );
+        assert_eq!(
+            registry.try_get("crc32").unwrap().finalize_truncated(8),
+            Err(HashBenchError::UnsupportedOutputLength {
+                requested: 8,
+                supported: 4
+            })
+        );
+        assert_eq!(
+            registry.try_get("crc32").unwrap().finalize_truncated(2),
+            Ok(vec![0, 0])
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Explicit SIMD backends of BLAKE3, BLAKE2b and CRC32, which normally pick a backend at runtime,
+//! so the gain of every backend can be measured on the same machine.
+//!
+//! Only the backends this CPU supports are returned. AVX-512 and NEON can't be requested from
+//! BLAKE3, so they are only included when BLAKE3 selects them.
+//!
+//! The backends are reached through the hidden benchmarking interfaces of blake3, blake2b_simd
+//! and crc32fast, which are not covered by semver and may need updating along with those crates.
+//! That's why this module is only built with the `simd-backends` feature.
+
+use std::fmt;
+
+/// The BLAKE3 IV, which is the key of unkeyed hashing.
+const BLAKE3_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+/// Flags of the first and last block of a BLAKE3 chunk.
+const BLAKE3_CHUNK_START: u8 = 1 << 0;
+const BLAKE3_CHUNK_END: u8 = 1 << 1;
+
+type BackendFn = Box<dyn Fn(&[u8], &mut Vec<u8>) + Send + Sync>;
+
+/// The BLAKE3 backends only compress whole chunks, which isn't comparable with a hasher.
+pub const BLAKE3_CHUNK_COMPRESSION: &str = "blake3-chunk-compression";
+
+/// A single SIMD backend of an algorithm.
+pub struct Backend {
+    /// The algorithm, named as in the registry where the output is the same, or
+    /// [`BLAKE3_CHUNK_COMPRESSION`].
+    pub algorithm: &'static str,
+    /// The instruction set used by the backend.
+    pub name: String,
+    /// Whether this backend is the one the algorithm selects at runtime on this CPU.
+    pub selected: bool,
+    hash: BackendFn,
+}
+
+impl Backend {
+    /// Hash `data` with this backend, replacing the contents of `out` with the output.
+    pub fn hash(&self, data: &[u8], out: &mut Vec<u8>) {
+        (self.hash)(data, out)
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.algorithm, self.name)?;
+        if self.selected {
+            f.write_str(" (selected)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every backend supported by this CPU.
+pub fn backends() -> Vec<Backend> {
+    let mut backends = blake3_backends();
+    backends.extend(blake2b_backends());
+    backends.extend(crc32_backends());
+    backends
+}
+
+/// BLAKE3 compresses whole chunks in parallel with SIMD, which is where its backends differ.
+/// These backends hash every full 1 KiB chunk of the input to its chaining value, and output the
+/// concatenated chaining values. A trailing partial chunk is ignored, and the chaining values are
+/// not merged into a tree, so this is a microbenchmark of the chunk compression rather than
+/// BLAKE3.
+fn blake3_backends() -> Vec<Backend> {
+    use blake3::platform::Platform;
+
+    let detected = Platform::detect();
+    #[allow(unused_mut)]
+    let mut platforms = vec![Platform::portable()];
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    platforms.extend(
+        [Platform::sse2(), Platform::sse41(), Platform::avx2()]
+            .into_iter()
+            .flatten(),
+    );
+    // AVX-512 and NEON can't be requested explicitly, but are used when detected.
+    if !platforms
+        .iter()
+        .any(|p| format!("{p:?}") == format!("{detected:?}"))
+    {
+        platforms.push(detected);
+    }
+
+    platforms
+        .into_iter()
+        .map(|platform| Backend {
+            algorithm: BLAKE3_CHUNK_COMPRESSION,
+            name: format!("{platform:?}").to_lowercase(),
+            selected: format!("{platform:?}") == format!("{detected:?}"),
+            hash: Box::new(move |data, out| {
+                let chunks: Vec<&[u8; blake3::CHUNK_LEN]> = data
+                    .chunks_exact(blake3::CHUNK_LEN)
+                    .map(|c| c.try_into().unwrap())
+                    .collect();
+                out.clear();
+                out.resize(chunks.len() * blake3::OUT_LEN, 0);
+                platform.hash_many(
+                    &chunks,
+                    &BLAKE3_IV,
+                    0,
+                    blake3::IncrementCounter::Yes,
+                    0,
+                    BLAKE3_CHUNK_START,
+                    BLAKE3_CHUNK_END,
+                    out,
+                );
+            }),
+        })
+        .collect()
+}
+
+/// The backend blake2b_simd selects, following its own detection order.
+fn blake2b_detected() -> &'static str {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return "avx2";
+        }
+        if is_x86_feature_detected!("sse4.1") {
+            return "sse4.1";
+        }
+    }
+    "portable"
+}
+
+/// blake2b_simd can only be forced to its portable backend, so that is compared against the
+/// backend it selects.
+fn blake2b_backends() -> Vec<Backend> {
+    let detected = blake2b_detected();
+    let mut backends = vec![
+        Backend {
+            algorithm: "blake2b-512",
+            name: "portable".into(),
+            selected: detected == "portable",
+            hash: Box::new(|data, out| {
+                let mut params = blake2b_simd::Params::new();
+                blake2b_simd::benchmarks::force_portable(&mut params);
+                out.clear();
+                out.extend_from_slice(params.hash(data).as_bytes());
+            }),
+        },
+        Backend {
+            algorithm: "blake2bp-512",
+            name: "portable".into(),
+            selected: detected == "portable",
+            hash: Box::new(|data, out| {
+                let mut params = blake2b_simd::blake2bp::Params::new();
+                blake2b_simd::benchmarks::force_portable_blake2bp(&mut params);
+                out.clear();
+                out.extend_from_slice(params.hash(data).as_bytes());
+            }),
+        },
+    ];
+    if detected != "portable" {
+        backends.push(Backend {
+            algorithm: "blake2b-512",
+            name: detected.into(),
+            selected: true,
+            hash: Box::new(|data, out| {
+                out.clear();
+                out.extend_from_slice(blake2b_simd::blake2b(data).as_bytes());
+            }),
+        });
+        backends.push(Backend {
+            algorithm: "blake2bp-512",
+            name: detected.into(),
+            selected: true,
+            hash: Box::new(|data, out| {
+                out.clear();
+                out.extend_from_slice(blake2b_simd::blake2bp::blake2bp(data).as_bytes());
+            }),
+        });
+    }
+    backends
+}
+
+/// crc32fast has a table based baseline, and a specialized backend using PCLMULQDQ on x86 or the
+/// CRC instructions on AArch64.
+fn crc32_backends() -> Vec<Backend> {
+    let specialized = crc32fast::Hasher::internal_new_specialized(0, 0).is_some();
+    let mut backends = vec![Backend {
+        algorithm: "crc32",
+        name: "baseline".into(),
+        selected: !specialized,
+        hash: Box::new(|data, out| {
+            let mut hasher = crc32fast::Hasher::internal_new_baseline(0, 0);
+            hasher.update(data);
+            out.clear();
+            out.extend_from_slice(&hasher.finalize().to_be_bytes());
+        }),
+    }];
+    if specialized {
+        backends.push(Backend {
+            algorithm: "crc32",
+            name: if cfg!(target_arch = "aarch64") {
+                "crc".into()
+            } else {
+                "pclmulqdq".into()
+            },
+            selected: true,
+            hash: Box::new(|data, out| {
+                let mut hasher = crc32fast::Hasher::internal_new_specialized(0, 0).unwrap();
+                hasher.update(data);
+                out.clear();
+                out.extend_from_slice(&hasher.finalize().to_be_bytes());
+            }),
+        });
+    }
+    backends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backends, BLAKE3_CHUNK_COMPRESSION};
+    use crate::registry::HasherRegistry;
+    use blake3::hazmat::HasherExt;
+
+    #[test]
+    fn backends_agree() {
+        let registry = HasherRegistry::builtin();
+        let data: Vec<u8> = (0..10_000).map(|i| (i % 251) as u8).collect();
+        let backends = backends();
+
+        let mut reference = Vec::new();
+        for (i, chunk) in data.chunks_exact(1024).enumerate() {
+            let mut hasher = blake3::Hasher::new();
+            hasher.set_input_offset(i as u64 * 1024).update(chunk);
+            reference.extend_from_slice(&hasher.finalize_non_root());
+        }
+
+        let mut out = Vec::new();
+        for backend in &backends {
+            backend.hash(&data, &mut out);
+            let expected = match backend.algorithm {
+                BLAKE3_CHUNK_COMPRESSION => reference.clone(),
+                name => registry.get(name).unwrap().hash(&data, data.len()),
+            };
+            assert_eq!(out, expected, "{backend}");
+        }
+
+        for algorithm in [
+            BLAKE3_CHUNK_COMPRESSION,
+            "blake2b-512",
+            "blake2bp-512",
+            "crc32",
+        ] {
+            let selected = backends
+                .iter()
+                .filter(|b| b.algorithm == algorithm && b.selected)
+                .count();
+            assert_eq!(selected, 1, "{algorithm}");
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Property tests asserting the digest of every hasher is independent of how its input is split.
+
+use hash_bench::{
+    hashers::{Blake3Hasher32, Blake3Hasher64, Hasher},
+    registry::HasherRegistry,
+};
+use proptest::prelude::*;
+
+/// An input together with a partition of it into consecutive, possibly empty, pieces.
+fn partitioned_input() -> impl Strategy<Value = (Vec<u8>, Vec<usize>)> {
+    prop::collection::vec(any::<u8>(), 0..4096).prop_flat_map(|data| {
+        let len = data.len();
+        // Duplicate cut points produce empty pieces, adjacent ones single bytes.
+        let cuts = prop::collection::vec(0..=len, 0..32).prop_map(|mut cuts| {
+            cuts.sort_unstable();
+            cuts
+        });
+        (Just(data), cuts)
+    })
+}
+
+fn pieces<'a>(data: &'a [u8], cuts: &'a [usize]) -> impl Iterator<Item = &'a [u8]> + 'a {
+    let starts = std::iter::once(0).chain(cuts.iter().copied());
+    let ends = cuts.iter().copied().chain(std::iter::once(data.len()));
+    starts.zip(ends).map(move |(start, end)| &data[start..end])
+}
+
+proptest! {
+    #[test]
+    fn partition_does_not_change_digest((data, cuts) in partitioned_input()) {
+        let registry = HasherRegistry::parallel();
+        for name in registry.names() {
+            let expected = registry.get(name).unwrap().hash(&data, data.len().max(1));
+
+            let mut hasher = registry.get(name).unwrap();
+            for piece in pieces(&data, &cuts) {
+                hasher.update(piece);
+            }
+            prop_assert_eq!(hasher.finalize(), expected.clone(), "{}", name);
+
+            let mut hasher = registry.get(name).unwrap();
+            hasher.update(b"state to be discarded");
+            hasher.update(&data);
+            hasher.reset();
+            for piece in pieces(&data, &cuts) {
+                hasher.update(piece);
+            }
+            prop_assert_eq!(hasher.finalize(), expected, "{} after reset", name);
+        }
+    }
+
+    #[test]
+    fn chunk_size_does_not_change_digest(
+        data in prop::collection::vec(any::<u8>(), 0..4096),
+        chunk_size in prop_oneof![Just(1usize), 1..64usize, 1..8192usize],
+    ) {
+        let registry = HasherRegistry::parallel();
+        for name in registry.names() {
+            prop_assert_eq!(
+                registry.get(name).unwrap().hash(&data, chunk_size),
+                registry.get(name).unwrap().hash(&data, data.len().max(1)),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn blake3_xof_extends_default_digest(
+        data in prop::collection::vec(any::<u8>(), 0..4096),
+        chunk_size in 1..8192usize,
+    ) {
+        let short = Blake3Hasher32::new().hash(&data, chunk_size);
+        let long = Blake3Hasher64::new().hash(&data, data.len().max(1));
+        prop_assert_eq!(&long[..32], &short[..]);
+
+        let mut output = [0; 64];
+        blake3::Hasher::new().update(&data).finalize_xof().fill(&mut output);
+        prop_assert_eq!(long, output);
+    }
+}
@@ -0,0 +1,786 @@
+//! Differential tests comparing hashers against independent reference implementations.
+//!
+//! Every reference is run against its registered hasher on random inputs. On a mismatch the input
+//! is shrunk to a minimal failing case, which is reported together with both digests. Every
+//! registered hasher needs a reference, unless it is listed in [`UNREFERENCED`].
+
+use digest::Digest;
+use hash_bench::registry::{HasherRegistry, BENCH_CONTEXT, BENCH_KEY, BENCH_PERSONA, BENCH_SALT};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestCaseError, TestError, TestRunner},
+};
+use tiny_keccak::{Sha3, Shake};
+
+type Reference = fn(&[u8]) -> Vec<u8>;
+
+/// Bit by bit CRC computation, directly following the parameter model of the CRC catalogue.
+fn bitwise_crc(data: &[u8], width: u32, poly: u64, init: u64, reflect: bool, xorout: u64) -> u64 {
+    let top = 1 << (width - 1);
+    let mask = u64::MAX >> (64 - width);
+    let mut crc = init;
+    for &byte in data {
+        let byte = if reflect { byte.reverse_bits() } else { byte };
+        crc ^= (byte as u64) << (width - 8);
+        for _ in 0..8 {
+            crc = if crc & top != 0 {
+                (crc << 1) ^ poly
+            } else {
+                crc << 1
+            } & mask;
+        }
+    }
+    if reflect {
+        crc = crc.reverse_bits() >> (64 - width);
+    }
+    crc ^ xorout
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn fletcher32(data: &[u8]) -> u32 {
+    let (mut sum1, mut sum2) = (0u32, 0u32);
+    for word in data.chunks(2) {
+        let word = u16::from_le_bytes([word[0], *word.get(1).unwrap_or(&0)]);
+        sum1 = (sum1 + word as u32) % 65535;
+        sum2 = (sum2 + sum1) % 65535;
+    }
+    (sum2 << 16) | sum1
+}
+
+fn murmur3_32(data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+    let mut h = 0u32;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let k = u32::from_le_bytes(block.try_into().unwrap())
+            .wrapping_mul(C1)
+            .rotate_left(15)
+            .wrapping_mul(C2);
+        h = (h ^ k)
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe6546b64);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, &byte) in tail.iter().enumerate() {
+            k |= (byte as u32) << (8 * i);
+        }
+        h ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^ (h >> 16)
+}
+
+/// MurmurHash3 x64 128 bit with seed 0, written as the reference implementation does: the two
+/// 64 bit halves in little endian order.
+fn murmur3_128(data: &[u8]) -> Vec<u8> {
+    const C1: u64 = 0x87c37b91114253d5;
+    const C2: u64 = 0x4cf5ad432745937f;
+    fn fmix(mut k: u64) -> u64 {
+        k ^= k >> 33;
+        k = k.wrapping_mul(0xff51afd7ed558ccd);
+        k ^= k >> 33;
+        k = k.wrapping_mul(0xc4ceb9fe1a85ec53);
+        k ^ (k >> 33)
+    }
+    let (mut h1, mut h2) = (0u64, 0u64);
+    let mut blocks = data.chunks_exact(16);
+    for block in &mut blocks {
+        let k1 = u64::from_le_bytes(block[..8].try_into().unwrap());
+        let k2 = u64::from_le_bytes(block[8..].try_into().unwrap());
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+        h1 = h1
+            .rotate_left(27)
+            .wrapping_add(h2)
+            .wrapping_mul(5)
+            .wrapping_add(0x52dce729);
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+        h2 = h2
+            .rotate_left(31)
+            .wrapping_add(h1)
+            .wrapping_mul(5)
+            .wrapping_add(0x38495ab5);
+    }
+    let tail = blocks.remainder();
+    let (mut k1, mut k2) = (0u64, 0u64);
+    for (i, &byte) in tail.iter().enumerate() {
+        if i < 8 {
+            k1 |= (byte as u64) << (8 * i);
+        } else {
+            k2 |= (byte as u64) << (8 * (i - 8));
+        }
+    }
+    if tail.len() > 8 {
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+    }
+    if !tail.is_empty() {
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+    }
+    h1 ^= data.len() as u64;
+    h2 ^= data.len() as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix(h1);
+    h2 = fmix(h2);
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    [h1.to_le_bytes(), h2.to_le_bytes()].concat()
+}
+
+// The SHA-2 constants of FIPS 180-4: the fractional parts of the cube roots of the first primes,
+// and of the square roots for the initial hash values.
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+const SHA224_IV: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// SHA-224 and SHA-256 as specified in FIPS 180-4, truncated to `len` bytes.
+fn sha256_family(data: &[u8], iv: [u32; 8], len: usize) -> Vec<u8> {
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    let mut h = iv;
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for t in 0..64 {
+            w[t] = if t < 16 {
+                u32::from_be_bytes(block[4 * t..4 * t + 4].try_into().unwrap())
+            } else {
+                let s0 = w[t - 15].rotate_right(7) ^ w[t - 15].rotate_right(18) ^ (w[t - 15] >> 3);
+                let s1 = w[t - 2].rotate_right(17) ^ w[t - 2].rotate_right(19) ^ (w[t - 2] >> 10);
+                s1.wrapping_add(w[t - 7])
+                    .wrapping_add(s0)
+                    .wrapping_add(w[t - 16])
+            };
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for t in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[t])
+                .wrapping_add(w[t]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (hh, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+    h.iter().flat_map(|h| h.to_be_bytes()).take(len).collect()
+}
+
+/// SHA-384, SHA-512 and SHA-512/t as specified in FIPS 180-4, truncated to `len` bytes.
+fn sha512_family(data: &[u8], iv: [u64; 8], len: usize) -> Vec<u8> {
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 128 != 112 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u128 * 8).to_be_bytes());
+
+    let mut h = iv;
+    for block in message.chunks(128) {
+        let mut w = [0u64; 80];
+        for t in 0..80 {
+            w[t] = if t < 16 {
+                u64::from_be_bytes(block[8 * t..8 * t + 8].try_into().unwrap())
+            } else {
+                let s0 = w[t - 15].rotate_right(1) ^ w[t - 15].rotate_right(8) ^ (w[t - 15] >> 7);
+                let s1 = w[t - 2].rotate_right(19) ^ w[t - 2].rotate_right(61) ^ (w[t - 2] >> 6);
+                s1.wrapping_add(w[t - 7])
+                    .wrapping_add(s0)
+                    .wrapping_add(w[t - 16])
+            };
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for t in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA512_K[t])
+                .wrapping_add(w[t]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (hh, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+    h.iter().flat_map(|h| h.to_be_bytes()).take(len).collect()
+}
+
+/// The initial hash value of SHA-512/t, generated as in section 5.3.6 of FIPS 180-4.
+fn sha512_t_iv(t: usize) -> [u64; 8] {
+    let iv = SHA512_IV.map(|h| h ^ 0xa5a5a5a5a5a5a5a5);
+    let digest = sha512_family(format!("SHA-512/{t}").as_bytes(), iv, 64);
+    std::array::from_fn(|i| u64::from_be_bytes(digest[8 * i..8 * i + 8].try_into().unwrap()))
+}
+
+const BLAKE3_CHUNK_START: u32 = 1 << 0;
+const BLAKE3_CHUNK_END: u32 = 1 << 1;
+const BLAKE3_PARENT: u32 = 1 << 2;
+const BLAKE3_ROOT: u32 = 1 << 3;
+const BLAKE3_KEYED_HASH: u32 = 1 << 4;
+const BLAKE3_DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const BLAKE3_DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+/// The BLAKE3 compression function, as in the reference implementation of the BLAKE3 paper. Its IV
+/// is that of SHA-256.
+fn blake3_compress(cv: &[u32; 8], block: &[u8], counter: u64, flags: u32) -> [u32; 16] {
+    const PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+    fn g(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+        s[a] = s[a].wrapping_add(s[b]).wrapping_add(x);
+        s[d] = (s[d] ^ s[a]).rotate_right(16);
+        s[c] = s[c].wrapping_add(s[d]);
+        s[b] = (s[b] ^ s[c]).rotate_right(12);
+        s[a] = s[a].wrapping_add(s[b]).wrapping_add(y);
+        s[d] = (s[d] ^ s[a]).rotate_right(8);
+        s[c] = s[c].wrapping_add(s[d]);
+        s[b] = (s[b] ^ s[c]).rotate_right(7);
+    }
+
+    let mut padded = [0; 64];
+    padded[..block.len()].copy_from_slice(block);
+    let mut m: [u32; 16] =
+        std::array::from_fn(|i| u32::from_le_bytes(padded[4 * i..4 * i + 4].try_into().unwrap()));
+    let mut s = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        SHA256_IV[0],
+        SHA256_IV[1],
+        SHA256_IV[2],
+        SHA256_IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block.len() as u32,
+        flags,
+    ];
+    for _ in 0..7 {
+        g(&mut s, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut s, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut s, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut s, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut s, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut s, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut s, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut s, 3, 4, 9, 14, m[14], m[15]);
+        m = PERMUTATION.map(|i| m[i]);
+    }
+    for i in 0..8 {
+        s[i] ^= s[i + 8];
+        s[i + 8] ^= cv[i];
+    }
+    s
+}
+
+/// The inputs of a compression which either gives a chaining value, or the root output.
+struct Blake3Node {
+    cv: [u32; 8],
+    block: Vec<u8>,
+    counter: u64,
+    flags: u32,
+}
+
+impl Blake3Node {
+    fn chaining_value(&self) -> [u32; 8] {
+        let out = blake3_compress(&self.cv, &self.block, self.counter, self.flags);
+        std::array::from_fn(|i| out[i])
+    }
+}
+
+/// The node of the subtree over `chunks`, of which the first has index `counter`. The left
+/// subtree holds the largest power of two number of chunks, leaving at least one to the right.
+fn blake3_subtree(chunks: &[&[u8]], counter: u64, key: &[u32; 8], flags: u32) -> Blake3Node {
+    if let [chunk] = chunks {
+        let blocks: Vec<&[u8]> = if chunk.is_empty() {
+            vec![&[]]
+        } else {
+            chunk.chunks(64).collect()
+        };
+        let mut cv = *key;
+        for (i, block) in blocks[..blocks.len() - 1].iter().enumerate() {
+            let start = if i == 0 { BLAKE3_CHUNK_START } else { 0 };
+            let out = blake3_compress(&cv, block, counter, flags | start);
+            cv = std::array::from_fn(|i| out[i]);
+        }
+        let start = if blocks.len() == 1 {
+            BLAKE3_CHUNK_START
+        } else {
+            0
+        };
+        return Blake3Node {
+            cv,
+            block: blocks[blocks.len() - 1].to_vec(),
+            counter,
+            flags: flags | start | BLAKE3_CHUNK_END,
+        };
+    }
+    let left = 1 << (usize::BITS - 1 - (chunks.len() - 1).leading_zeros());
+    let left_cv = blake3_subtree(&chunks[..left], counter, key, flags).chaining_value();
+    let right_cv =
+        blake3_subtree(&chunks[left..], counter + left as u64, key, flags).chaining_value();
+    Blake3Node {
+        cv: *key,
+        block: left_cv
+            .iter()
+            .chain(&right_cv)
+            .flat_map(|w| w.to_le_bytes())
+            .collect(),
+        counter: 0,
+        flags: flags | BLAKE3_PARENT,
+    }
+}
+
+/// BLAKE3 of `data` with the given key words and mode flags, producing `len` bytes of output.
+fn blake3_reference(data: &[u8], key: &[u32; 8], flags: u32, len: usize) -> Vec<u8> {
+    let chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(1024).collect()
+    };
+    let root = blake3_subtree(&chunks, 0, key, flags);
+    (0..)
+        .flat_map(|counter| {
+            blake3_compress(&root.cv, &root.block, counter, root.flags | BLAKE3_ROOT)
+                .map(u32::to_le_bytes)
+        })
+        .flatten()
+        .take(len)
+        .collect()
+}
+
+fn blake3_key_words(key: &[u8]) -> [u32; 8] {
+    std::array::from_fn(|i| u32::from_le_bytes(key[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+fn blake3_derive_key(context: &str, material: &[u8]) -> Vec<u8> {
+    let context_key = blake3_reference(
+        context.as_bytes(),
+        &SHA256_IV,
+        BLAKE3_DERIVE_KEY_CONTEXT,
+        32,
+    );
+    blake3_reference(
+        material,
+        &blake3_key_words(&context_key),
+        BLAKE3_DERIVE_KEY_MATERIAL,
+        32,
+    )
+}
+
+/// HMAC as defined in RFC 2104, built directly on the hash function.
+fn hmac<D: Digest + digest::core_api::BlockSizeUser>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let block_size = D::block_size();
+    let mut block_key = if key.len() > block_size {
+        D::digest(key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    block_key.resize(block_size, 0);
+
+    let inner_key: Vec<u8> = block_key.iter().map(|b| b ^ 0x36).collect();
+    let outer_key: Vec<u8> = block_key.iter().map(|b| b ^ 0x5c).collect();
+    let inner = D::new()
+        .chain_update(inner_key)
+        .chain_update(data)
+        .finalize();
+    D::new()
+        .chain_update(outer_key)
+        .chain_update(inner)
+        .finalize()
+        .to_vec()
+}
+
+fn blake2b_simd(data: &[u8], len: usize, keyed: bool) -> Vec<u8> {
+    let mut params = blake2b_simd::Params::new();
+    params.hash_length(len);
+    if keyed {
+        params
+            .key(&BENCH_KEY)
+            .salt(&BENCH_SALT)
+            .personal(&BENCH_PERSONA);
+    }
+    params.hash(data).as_bytes().to_vec()
+}
+
+/// BLAKE2bp from plain BLAKE2b in tree mode: 4 leaves each hash every 4th 128 byte block, and the
+/// root hashes the concatenated leaf digests.
+fn blake2bp_tree(data: &[u8]) -> Vec<u8> {
+    let mut root = blake2b_simd::Params::new();
+    root.fanout(4)
+        .max_depth(2)
+        .node_depth(1)
+        .inner_hash_length(64)
+        .last_node(true);
+    let mut root = root.to_state();
+    for i in 0..4 {
+        let mut leaf = blake2b_simd::Params::new();
+        leaf.fanout(4)
+            .max_depth(2)
+            .node_offset(i as u64)
+            .inner_hash_length(64)
+            .last_node(i == 3);
+        let mut leaf = leaf.to_state();
+        for block in data.chunks(128).skip(i).step_by(4) {
+            leaf.update(block);
+        }
+        root.update(leaf.finalize().as_bytes());
+    }
+    root.finalize().as_bytes().to_vec()
+}
+
+/// BLAKE2sp from plain BLAKE2s in tree mode: 8 leaves each hash every 8th 64 byte block, and the
+/// root hashes the concatenated leaf digests.
+fn blake2sp_tree(data: &[u8]) -> Vec<u8> {
+    let mut root = blake2s_simd::Params::new();
+    root.fanout(8)
+        .max_depth(2)
+        .node_depth(1)
+        .inner_hash_length(32)
+        .last_node(true);
+    let mut root = root.to_state();
+    for i in 0..8 {
+        let mut leaf = blake2s_simd::Params::new();
+        leaf.fanout(8)
+            .max_depth(2)
+            .node_offset(i as u64)
+            .inner_hash_length(32)
+            .last_node(i == 7);
+        let mut leaf = leaf.to_state();
+        for block in data.chunks(64).skip(i).step_by(8) {
+            leaf.update(block);
+        }
+        root.update(leaf.finalize().as_bytes());
+    }
+    root.finalize().as_bytes().to_vec()
+}
+
+fn k12(data: &[u8]) -> Vec<u8> {
+    use digest::{ExtendableOutput, Update};
+
+    let mut hasher = k12::KangarooTwelve::from_core(k12::KangarooTwelveCore::new(&[]));
+    hasher.update(data);
+    let mut output = vec![0; 32];
+    hasher.finalize_xof_into(&mut output);
+    output
+}
+
+fn tiny_keccak(mut hasher: impl tiny_keccak::Hasher, data: &[u8], len: usize) -> Vec<u8> {
+    let mut output = vec![0; len];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+const REFERENCES: &[(&str, Reference)] = &[
+    ("md5", |d| md_5_reference::Md5::digest(d).to_vec()),
+    ("blake2b-256", |d| blake2b_simd(d, 32, false)),
+    ("blake3-256", |d| blake3_reference(d, &SHA256_IV, 0, 32)),
+    ("blake3-512", |d| blake3_reference(d, &SHA256_IV, 0, 64)),
+    ("blake3-256-rayon", |d| {
+        blake3_reference(d, &SHA256_IV, 0, 32)
+    }),
+    ("blake3-256-keyed", |d| {
+        blake3_reference(d, &blake3_key_words(&BENCH_KEY), BLAKE3_KEYED_HASH, 32)
+    }),
+    ("blake3-256-derive-key", |d| {
+        blake3_derive_key(BENCH_CONTEXT, d)
+    }),
+    ("blake2b-512", |d| blake2b_simd(d, 64, false)),
+    ("blake2b-256-mac", |d| blake2b_simd(d, 32, true)),
+    ("blake2b-512-mac", |d| blake2b_simd(d, 64, true)),
+    ("blake2bp-512", blake2bp_tree),
+    ("blake2sp-256", blake2sp_tree),
+    ("crc32", |d| {
+        (bitwise_crc(d, 32, 0x04c11db7, 0xffffffff, true, 0xffffffff) as u32)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("crc32-table", |d| {
+        (bitwise_crc(d, 32, 0x04c11db7, 0xffffffff, true, 0xffffffff) as u32)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("crc32c", |d| {
+        (bitwise_crc(d, 32, 0x1edc6f41, 0xffffffff, true, 0xffffffff) as u32)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("crc32c-table", |d| {
+        (bitwise_crc(d, 32, 0x1edc6f41, 0xffffffff, true, 0xffffffff) as u32)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("crc64-ecma", |d| {
+        bitwise_crc(d, 64, 0x42f0e1eba9ea3693, 0, false, 0)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("crc64-nvme", |d| {
+        bitwise_crc(d, 64, 0xad93d23594c93659, u64::MAX, true, u64::MAX)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("crc64-nvme-table", |d| {
+        bitwise_crc(d, 64, 0xad93d23594c93659, u64::MAX, true, u64::MAX)
+            .to_be_bytes()
+            .to_vec()
+    }),
+    ("adler32", |d| adler32(d).to_be_bytes().to_vec()),
+    ("fletcher32", |d| fletcher32(d).to_be_bytes().to_vec()),
+    ("sha224", |d| sha256_family(d, SHA224_IV, 28)),
+    ("sha256", |d| sha256_family(d, SHA256_IV, 32)),
+    ("sha384", |d| sha512_family(d, SHA384_IV, 48)),
+    ("sha512", |d| sha512_family(d, SHA512_IV, 64)),
+    ("sha512-256", |d| sha512_family(d, sha512_t_iv(256), 32)),
+    ("sha3-256", |d| tiny_keccak(Sha3::v256(), d, 32)),
+    ("sha3-512", |d| tiny_keccak(Sha3::v512(), d, 64)),
+    ("shake128-256", |d| tiny_keccak(Shake::v128(), d, 32)),
+    ("shake256-512", |d| tiny_keccak(Shake::v256(), d, 64)),
+    ("k12-256", k12),
+    ("hmac-sha256", |d| hmac::<sha2::Sha256>(&BENCH_KEY, d)),
+    ("hmac-sha512", |d| hmac::<sha2::Sha512>(&BENCH_KEY, d)),
+    ("hmac-sha3-256", |d| hmac::<sha3::Sha3_256>(&BENCH_KEY, d)),
+    ("xxh32", |d| {
+        twox_hash::XxHash32::oneshot(0, d).to_be_bytes().to_vec()
+    }),
+    ("xxh64", |d| {
+        twox_hash::XxHash64::oneshot(0, d).to_be_bytes().to_vec()
+    }),
+    ("xxh3-64", |d| {
+        twox_hash::XxHash3_64::oneshot(d).to_be_bytes().to_vec()
+    }),
+    ("xxh3-128", |d| {
+        twox_hash::XxHash3_128::oneshot(d).to_be_bytes().to_vec()
+    }),
+    ("murmur3-32", |d| murmur3_32(d).to_be_bytes().to_vec()),
+    ("murmur3-128", murmur3_128),
+];
+
+/// Registered hashers without an independent implementation to compare against, which are only
+/// checked against known answers.
+const UNREFERENCED: &[&str] = &[
+    // The wyhash crate implements an early version of wyhash, whose output differs from that of
+    // later versions, and no other implementation of it exists.
+    "wyhash",
+];
+
+/// Run `reference` against the registered hasher `name`, returning a description of the minimal
+/// failing input, if any.
+fn check(registry: &HasherRegistry, name: &str, reference: Reference) -> Option<String> {
+    let mut runner = TestRunner::new(Config {
+        cases: 2000,
+        failure_persistence: None,
+        ..Config::default()
+    });
+    let result = runner.run(&prop::collection::vec(any::<u8>(), 0..1024), |data| {
+        let digest = registry.get(name).unwrap().hash(&data, 61);
+        if digest == reference(&data) {
+            Ok(())
+        } else {
+            Err(TestCaseError::fail("digest differs from reference"))
+        }
+    });
+
+    match result {
+        Ok(()) => None,
+        Err(TestError::Fail(_, data)) => Some(format!(
+            "{name}: input {data:02x?} hashes to {:02x?}, reference gives {:02x?}",
+            registry.get(name).unwrap().hash(&data, 61),
+            reference(&data)
+        )),
+        Err(e) => Some(format!("{name}: {e}")),
+    }
+}
+
+#[test]
+fn hashers_match_references() {
+    let registry = &HasherRegistry::parallel();
+    for name in registry.names() {
+        assert!(
+            REFERENCES.iter().any(|&(r, _)| r == name) || UNREFERENCED.contains(&name),
+            "no reference for {name}"
+        );
+    }
+
+    let failures: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = REFERENCES
+            .iter()
+            .map(|&(name, reference)| s.spawn(move || check(registry, name, reference)))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// The random inputs fit in a single BLAKE3 chunk, so the trees of the BLAKE3 reference are
+/// checked separately.
+#[test]
+fn blake3_trees_match_reference() {
+    let registry = HasherRegistry::parallel();
+    let data: Vec<u8> = (0..17 * 1024 + 1).map(|i| (i % 251) as u8).collect();
+    for len in [1025, 2048, 3073, 4096, 5121, 8193, 17 * 1024 + 1] {
+        assert_eq!(
+            registry.get("blake3-256").unwrap().hash(&data[..len], 1000),
+            blake3_reference(&data[..len], &SHA256_IV, 0, 32),
+            "length {len}"
+        );
+    }
+}
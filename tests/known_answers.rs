@@ -0,0 +1,190 @@
+//! Checks every hasher against the known answer vectors in `tests/vectors`.
+//!
+//! Each non empty line in a vector file which does not start with `#` has the form
+//!
+//! ```text
+//! <algorithm> <input> <expected digest in hex> [key=<input>]
+//! ```
+//!
+//! where `algorithm` is a name in [`HasherRegistry::parallel`], and an input is one of
+//! - `"text"`: the ASCII bytes between the quotes,
+//! - `hex:<bytes>`: hex encoded bytes,
+//! - `pattern:<n>`: the `n` bytes `0, 1, ..., 250, 0, 1, ...`,
+//! - `repeat:<n>:<bytes>`: hex encoded bytes repeated up to a length of `n` bytes.
+//!
+//! The key is used to construct keyed hashers in place of the benchmark keys of the registry.
+//! For BLAKE3 key derivation it is the context string, and for wyhash the seed as 8 little endian
+//! bytes.
+
+use hash_bench::{
+    hashers::{
+        Blake2bMac32, Blake2bMac64, Blake3DeriveKeyHasher32, Blake3KeyedHasher32, Hasher,
+        HmacSha256Hasher, HmacSha3_256Hasher, HmacSha512Hasher,
+    },
+    registry::{DynHasher, HasherRegistry},
+};
+use std::{collections::BTreeSet, fs, path::Path};
+
+struct Vector {
+    location: String,
+    algorithm: String,
+    input: Vec<u8>,
+    expected: Vec<u8>,
+    key: Option<Vec<u8>>,
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    assert!(hex.len().is_multiple_of(2), "odd length hex string {hex}");
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex"))
+        .collect()
+}
+
+fn parse_input(spec: &str) -> Vec<u8> {
+    if let Some(text) = spec.strip_prefix('"') {
+        text.strip_suffix('"').expect("unterminated string").into()
+    } else if let Some(hex) = spec.strip_prefix("hex:") {
+        decode_hex(hex)
+    } else if let Some(len) = spec.strip_prefix("pattern:") {
+        (0..len.parse().unwrap())
+            .map(|i: usize| (i % 251) as u8)
+            .collect()
+    } else if let Some(repeat) = spec.strip_prefix("repeat:") {
+        let (len, hex) = repeat.split_once(':').expect("missing repeated bytes");
+        decode_hex(hex)
+            .into_iter()
+            .cycle()
+            .take(len.parse().unwrap())
+            .collect()
+    } else {
+        panic!("unknown input {spec}")
+    }
+}
+
+/// Split a line on whitespace, keeping quoted strings together.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let end = match rest.find('"') {
+            // A quoted string may contain whitespace, so the token ends at the closing quote.
+            Some(open) if !rest[..open].contains(char::is_whitespace) => {
+                open + 2 + rest[open + 1..].find('"').expect("unterminated string")
+            }
+            _ => rest.find(char::is_whitespace).unwrap_or(rest.len()),
+        };
+        tokens.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    tokens
+}
+
+fn load_vectors() -> Vec<Vector> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/vectors");
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+
+    let mut vectors = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file).unwrap();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let location = format!("{}:{}", file.display(), i + 1);
+            let (algorithm, input, expected, key) = match tokenize(line)[..] {
+                [algorithm, input, expected] => (algorithm, input, expected, None),
+                [algorithm, input, expected, key] => (
+                    algorithm,
+                    input,
+                    expected,
+                    Some(key.strip_prefix("key=").expect("expected key=")),
+                ),
+                _ => panic!("{location}: malformed vector"),
+            };
+            vectors.push(Vector {
+                location,
+                algorithm: algorithm.into(),
+                input: parse_input(input),
+                expected: decode_hex(expected),
+                key: key.map(parse_input),
+            });
+        }
+    }
+    vectors
+}
+
+/// wyhash with a seed, which the registry fixes at 0.
+struct SeededWyHasher {
+    seed: u64,
+    buffer: Vec<u8>,
+}
+
+impl Hasher for SeededWyHasher {
+    type Output = [u8; 8];
+
+    fn update(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        wyhash::wyhash(&self.buffer, self.seed).to_be_bytes()
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+fn keyed_hasher(algorithm: &str, key: &[u8]) -> Box<dyn DynHasher> {
+    match algorithm {
+        "blake3-256-keyed" => Box::new(Blake3KeyedHasher32::new(
+            key.try_into().expect("BLAKE3 keys are 32 bytes"),
+        )),
+        "blake3-256-derive-key" => Box::new(Blake3DeriveKeyHasher32::new(
+            std::str::from_utf8(key).expect("context must be UTF-8"),
+        )),
+        "blake2b-256-mac" => Box::new(Blake2bMac32::new(key, b"", b"").unwrap()),
+        "blake2b-512-mac" => Box::new(Blake2bMac64::new(key, b"", b"").unwrap()),
+        "hmac-sha256" => Box::new(HmacSha256Hasher::new(key)),
+        "hmac-sha512" => Box::new(HmacSha512Hasher::new(key)),
+        "hmac-sha3-256" => Box::new(HmacSha3_256Hasher::new(key)),
+        "wyhash" => Box::new(SeededWyHasher {
+            seed: u64::from_le_bytes(key.try_into().expect("wyhash seeds are 8 bytes")),
+            buffer: Vec::new(),
+        }),
+        _ => panic!("{algorithm} does not take a key"),
+    }
+}
+
+#[test]
+fn known_answers() {
+    let registry = HasherRegistry::parallel();
+    let vectors = load_vectors();
+
+    for vector in &vectors {
+        for chunk_size in [1, 7, 64, 1024, vector.input.len().max(1)] {
+            let hasher = match &vector.key {
+                Some(key) => keyed_hasher(&vector.algorithm, key),
+                None => registry
+                    .get(&vector.algorithm)
+                    .unwrap_or_else(|| panic!("{}: unknown algorithm", vector.location)),
+            };
+            assert_eq!(
+                hasher.hash(&vector.input, chunk_size),
+                vector.expected,
+                "{}: chunk size {chunk_size}",
+                vector.location
+            );
+        }
+    }
+
+    let covered: BTreeSet<_> = vectors.iter().map(|v| v.algorithm.as_str()).collect();
+    for name in registry.names() {
+        assert!(covered.contains(name), "no known answers for {name}");
+    }
+}